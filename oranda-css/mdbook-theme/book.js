@@ -316,14 +316,18 @@ function playground_text(playground, hidden = true) {
     }
 
     function checkThemeCacheValidity() {
-        // We use the display name of the default_theme to determine if the oranda
+        // We use the display names of the oranda themes to determine if the oranda
         // user has changed the default theme. If they have, we invalidate the stored
-        // theme so that we'll revert back to the default_theme.
+        // theme so that we'll revert back to the default_theme. (Not just default_theme's,
+        // since that follows prefers-color-scheme, and the oranda pages check this too.)
         var oldLabel;
         try {
             oldLabel = localStorage.getItem('orandamdbook-theme-default-label');
         } catch (e) { }
-        var curLabel = themePopup.querySelector("button#" + default_theme).innerText;
+        var curLabel = Array.prototype.map.call(
+            themePopup.querySelectorAll("button#oranda-dark, button#oranda-light"),
+            function (button) { return button.textContent; }
+        ).join(" / ");
 
         if (oldLabel != curLabel) {
             // Default changed, toss everything
@@ -4,6 +4,7 @@ use clap::Parser;
 use oranda::config::Config;
 
 use oranda::errors::*;
+use oranda::http_cache::HttpCache;
 use oranda::site::Site;
 
 #[derive(Debug, Parser)]
@@ -26,18 +27,29 @@ pub struct Build {
     /// support it, such as installer source files.
     #[arg(long)]
     json_only: bool,
+    /// Build without touching the network, using only what previous builds cached
+    ///
+    /// Fails if something we need (like your releases) was never fetched before.
+    #[arg(long)]
+    offline: bool,
 }
 
 impl Build {
-    pub fn new(project_root: Option<Utf8PathBuf>, config_path: Option<Utf8PathBuf>) -> Self {
+    pub fn new(
+        project_root: Option<Utf8PathBuf>,
+        config_path: Option<Utf8PathBuf>,
+        offline: bool,
+    ) -> Self {
         Build {
             project_root: project_root.unwrap_or(Utf8PathBuf::from("./")),
             config_path: config_path.unwrap_or(Utf8PathBuf::from("./oranda.json")),
             json_only: false,
+            offline,
         }
     }
 
     pub fn run(&self) -> Result<()> {
+        HttpCache::set_global(Some(HttpCache::new(HttpCache::default_dir(), self.offline)));
         if let Some(config) = Site::get_workspace_config()? {
             let sites = Site::build_multi(&config, self.json_only)?;
             if config.workspace.generate_index && !self.json_only {
@@ -45,6 +45,9 @@ pub struct Dev {
     /// List of extra paths to watch
     #[arg(short, long)]
     include_paths: Option<Vec<Utf8PathBuf>>,
+    /// Build without touching the network, using only what previous builds cached
+    #[arg(long)]
+    offline: bool,
 }
 
 impl Dev {
@@ -121,7 +124,12 @@ impl Dev {
         tracing::debug!("Files watched: {:?}", existing_paths);
 
         if !self.no_first_build {
-            Build::new(self.project_root.clone(), self.config_path.clone()).run()?;
+            Build::new(
+                self.project_root.clone(),
+                self.config_path.clone(),
+                self.offline,
+            )
+            .run()?;
         }
 
         let (ws_tx, ws_rx) = std::sync::mpsc::channel();
@@ -166,8 +174,12 @@ impl Dev {
             if !paths.is_empty() {
                 tracing::info!("Path(s) {:?} changed, rebuilding...", paths);
 
-                if let Err(e) =
-                    Build::new(self.project_root.clone(), self.config_path.clone()).run()
+                if let Err(e) = Build::new(
+                    self.project_root.clone(),
+                    self.config_path.clone(),
+                    self.offline,
+                )
+                .run()
                 {
                     eprintln!("{:?}", Report::new(e));
                     continue;
@@ -220,6 +232,11 @@ impl Dev {
             }
         }
 
+        // Watch for data files for templates
+        if let Some(path) = determine_path(root_path, &member_path, config.build.data_dir())? {
+            paths_to_watch.push(path);
+        }
+
         // Watch for additional pages, if we have any
         if !config.build.additional_pages.is_empty() {
             let mut additional_pages = config
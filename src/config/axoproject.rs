@@ -1,9 +1,9 @@
 use crate::config::workspace::WorkspaceMember;
-use axoproject::{PackageIdx, WorkspaceInfo, WorkspaceSearch};
+use axoproject::{PackageIdx, PackageInfo, WorkspaceInfo, WorkspaceKind, WorkspaceSearch};
 use camino::{Utf8Path, Utf8PathBuf};
 use std::path::PathBuf;
 
-use super::ProjectLayer;
+use super::{PackageManager, PackageManagersConfig, ProjectLayer};
 use crate::errors::*;
 
 /// Info gleaned from axoproject
@@ -13,6 +13,10 @@ pub struct AxoprojectLayer {
     pub project: Option<ProjectLayer>,
     /// Did they have cargo_dist settings?
     pub cargo_dist: Option<bool>,
+    /// Did they have cargo-dist make GitHub attestations?
+    pub github_attestations: Option<bool>,
+    /// Package managers the project can be installed with, based on the manifest
+    pub package_managers: Option<PackageManagersConfig>,
     /// Information about workspace packages
     pub members: Option<Vec<WorkspaceMember>>,
 }
@@ -45,6 +49,11 @@ impl AxoprojectLayer {
                 .cargo_metadata_table
                 .as_ref()
                 .map(|t| t.get("dist").is_some());
+            let github_attestations = workspace
+                .cargo_metadata_table
+                .as_ref()
+                .and_then(|t| t.get("dist")?.get("github-attestations")?.as_bool());
+            let package_managers = Self::infer_package_managers(&workspace, package);
             Ok(Some(AxoprojectLayer {
                 project: Some(ProjectLayer {
                     name: Some(package.name.clone()),
@@ -56,6 +65,8 @@ impl AxoprojectLayer {
                     readme_path: package.readme_file.as_ref().map(|v| v.to_string()),
                 }),
                 cargo_dist,
+                github_attestations,
+                package_managers,
                 members: None,
             }))
         } else {
@@ -85,10 +96,54 @@ impl AxoprojectLayer {
         Ok(Some(AxoprojectLayer {
             project: None,
             cargo_dist: None,
+            github_attestations: None,
+            package_managers: None,
             members: Some(members),
         }))
     }
 
+    /// Suggest ways to install a package with its ecosystem's package manager
+    ///
+    /// We only do this for packages that are published and actually have binaries to install.
+    fn infer_package_managers(
+        workspace: &WorkspaceInfo,
+        package: &PackageInfo,
+    ) -> Option<PackageManagersConfig> {
+        if !package.publish || package.binaries.is_empty() {
+            return None;
+        }
+        let name = &package.name;
+        let mut package_managers = PackageManagersConfig::default();
+        match workspace.kind {
+            WorkspaceKind::Rust => {
+                package_managers.preferred.insert(
+                    "cargo".to_owned(),
+                    PackageManager::new(format!("cargo install {name} --locked")),
+                );
+                let has_binstall = package
+                    .cargo_metadata_table
+                    .as_ref()
+                    .is_some_and(|t| t.get("binstall").is_some());
+                if has_binstall {
+                    package_managers.additional.insert(
+                        "binstall".to_owned(),
+                        PackageManager::new(format!("cargo binstall {name}")),
+                    );
+                }
+            }
+            WorkspaceKind::Javascript => {
+                package_managers.preferred.insert(
+                    "npm".to_owned(),
+                    PackageManager::new(format!("npm install -g {name}")),
+                );
+                package_managers
+                    .additional
+                    .insert("npx".to_owned(), PackageManager::new(format!("npx {name}")));
+            }
+        }
+        Some(package_managers)
+    }
+
     /// Given context, fetches workspaces and returns whichever "wins". Right now, this means Cargo
     /// projects always win over JS projects, but this will change in the future as we introduce more
     /// criteria.
@@ -11,6 +11,9 @@ pub struct BuildConfig {
     pub dist_dir: String,
     /// Relative path to a dir full of extra static content
     pub static_dir: String,
+    /// Relative path to a dir full of data files (JSON, YAML or TOML) for templates, if it was
+    /// set explicitly (see [`BuildConfig::data_dir`][])
+    pub data_dir: Option<String>,
     /// A path fragment to prepend before URLs
     ///
     /// This allows things like hosting a static site at `axodotdev.github.io/my_project/`
@@ -21,6 +24,8 @@ pub struct BuildConfig {
     ///
     /// We use IndexMap to respect the order the user provided.
     pub additional_pages: IndexMap<String, String>,
+    /// The order, labels and icons of the items in the nav, and any external links in it
+    pub nav: Vec<NavItem>,
 }
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -36,6 +41,14 @@ pub struct BuildLayer {
     ///
     /// This is "./static/" by default
     pub static_dir: Option<String>,
+    /// Relative path to a dir full of data files (JSON, YAML or TOML)
+    ///
+    /// Each file is available to templates under `data`, named after its path
+    /// (so `data/team/members.yml` is `data.team.members`).
+    ///
+    /// This is "./data/" by default. Files there that fail to parse are skipped with a warning,
+    /// since the directory might belong to something else, but if you set this they're errors.
+    pub data_dir: Option<String>,
     /// A path fragment to prepend before URLs
     ///
     /// This allows things like hosting a static site at `axodotdev.github.io/my_project/`
@@ -44,11 +57,71 @@ pub struct BuildLayer {
     /// Additional pages that should be included in the top level nav.
     ///
     /// This is a map from page-label to relative paths to (Github Flavored) Markdown files
-    /// that should be rendered into pages.
+    /// that should be rendered into pages. Files ending in `.j2` or `.jinja` are rendered
+    /// as templates instead, with the same context as oranda's own templates.
     ///
     /// These pages will be listed in the given order after "home" and before
     /// other pages that oranda automatically adds like "install" and "funding".
     pub additional_pages: Option<IndexMap<String, String>>,
+    /// The items in the top level nav, in order.
+    ///
+    /// Each item is a built-in page (`builtin`), one of your `additional_pages` (`page`, by its
+    /// name) or an external link (`url`), with an optional custom `label` and `icon`.
+    ///
+    /// Built-in pages and additional pages you don't mention keep their usual place.
+    pub nav: Option<Vec<NavItem>>,
+}
+
+/// An item in the top level nav
+///
+/// Exactly one of `builtin`, `page` and `url` should be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NavItem {
+    /// A page oranda makes: "home", "install", "docs", "funding" or "changelog"
+    pub builtin: Option<NavBuiltin>,
+    /// The name of one of your `additional_pages`
+    pub page: Option<String>,
+    /// A link to somewhere else
+    pub url: Option<String>,
+    /// The text of the link (the page's usual name by default, and required for `url`)
+    pub label: Option<String>,
+    /// Path or URL to an image to show before the label
+    pub icon: Option<String>,
+}
+
+/// A page oranda makes that can be in the nav
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NavBuiltin {
+    Home,
+    Install,
+    Docs,
+    Funding,
+    Changelog,
+}
+
+impl NavBuiltin {
+    /// The usual label for the page
+    pub fn label(&self) -> &'static str {
+        match self {
+            NavBuiltin::Home => "Home",
+            NavBuiltin::Install => "Install",
+            NavBuiltin::Docs => "Docs",
+            NavBuiltin::Funding => "Funding",
+            NavBuiltin::Changelog => "Changelog",
+        }
+    }
+}
+
+impl BuildConfig {
+    /// Where to look for data files if `data_dir` isn't set
+    pub const DEFAULT_DATA_DIR: &'static str = "data";
+
+    /// The dir to look for data files in
+    pub fn data_dir(&self) -> &str {
+        self.data_dir.as_deref().unwrap_or(Self::DEFAULT_DATA_DIR)
+    }
 }
 
 impl Default for BuildConfig {
@@ -56,8 +129,10 @@ impl Default for BuildConfig {
         BuildConfig {
             dist_dir: "public".to_owned(),
             static_dir: "static".to_owned(),
+            data_dir: None,
             path_prefix: None,
             additional_pages: Default::default(),
+            nav: Vec::new(),
         }
     }
 }
@@ -68,13 +143,17 @@ impl ApplyLayer for BuildConfig {
         let BuildLayer {
             dist_dir,
             static_dir,
+            data_dir,
             path_prefix,
             additional_pages,
+            nav,
         } = layer;
         self.dist_dir.apply_val(dist_dir);
         self.static_dir.apply_val(static_dir);
+        self.data_dir.apply_opt(data_dir);
         self.path_prefix.apply_opt(path_prefix);
         // In the future this might want to be `extend`
         self.additional_pages.apply_val(additional_pages);
+        self.nav.apply_val(nav);
     }
 }
@@ -1,14 +1,20 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::config::{ApplyLayer, ApplyValExt};
+use crate::config::{ApplyLayer, ApplyOptExt, ApplyValExt};
 
 mod package_managers;
-pub use package_managers::{PackageManagersConfig, PackageManagersLayer};
+pub use package_managers::{
+    InstallerPreference, PackageManager, PackageManagerFullLayer, PackageManagerLayer,
+    PackageManagersConfig, PackageManagersLayer,
+};
 
-#[derive(Debug, Deserialize, JsonSchema)]
+/// An operating system (or Linux distro) an artifact can be native to
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
-enum ArtifactSystem {
+pub enum ArtifactSystem {
     Windows,
     Windows64,
     WindowsArm,
@@ -33,13 +39,118 @@ enum ArtifactSystem {
     Freebsd,
 }
 
+impl ArtifactSystem {
+    /// The Linux distros that .deb packages are native to
+    pub const DEB_DISTROS: &'static [ArtifactSystem] =
+        &[ArtifactSystem::LinuxDebian, ArtifactSystem::LinuxUbuntu];
+    /// The Linux distros that .rpm packages are native to
+    pub const RPM_DISTROS: &'static [ArtifactSystem] = &[
+        ArtifactSystem::LinuxFedora,
+        ArtifactSystem::LinuxRedhat,
+        ArtifactSystem::LinuxSuse,
+        ArtifactSystem::LinuxMandriva,
+    ];
+
+    /// Every system, for looking them up by [`ArtifactSystem::id`][]
+    pub const ALL: &'static [ArtifactSystem] = &[
+        ArtifactSystem::Windows,
+        ArtifactSystem::Windows64,
+        ArtifactSystem::WindowsArm,
+        ArtifactSystem::Mac,
+        ArtifactSystem::MacPpc,
+        ArtifactSystem::Mac32,
+        ArtifactSystem::MacSilicon,
+        ArtifactSystem::Linux,
+        ArtifactSystem::LinuxUbuntu,
+        ArtifactSystem::LinuxDebian,
+        ArtifactSystem::LinuxMandriva,
+        ArtifactSystem::LinuxRedhat,
+        ArtifactSystem::LinuxFedora,
+        ArtifactSystem::LinuxSuse,
+        ArtifactSystem::LinuxGentoo,
+        ArtifactSystem::Ios,
+        ArtifactSystem::Android,
+        ArtifactSystem::Freebsd,
+    ];
+
+    /// Look up a system by its [`ArtifactSystem::id`][]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|system| system.id() == id)
+    }
+
+    /// Whether this is a specific Linux distro (rather than Linux in general)
+    pub fn is_linux_distro(&self) -> bool {
+        *self != ArtifactSystem::Linux && self.id().starts_with("linux")
+    }
+
+    /// A human-readable name for the system
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ArtifactSystem::Windows => "Windows",
+            ArtifactSystem::Windows64 => "Windows x64",
+            ArtifactSystem::WindowsArm => "Windows arm64",
+            ArtifactSystem::Mac => "macOS",
+            ArtifactSystem::MacPpc => "macOS PowerPC",
+            ArtifactSystem::Mac32 => "macOS x86",
+            ArtifactSystem::MacSilicon => "macOS Apple Silicon",
+            ArtifactSystem::Linux => "Linux",
+            ArtifactSystem::LinuxUbuntu => "Ubuntu",
+            ArtifactSystem::LinuxDebian => "Debian",
+            ArtifactSystem::LinuxMandriva => "Mandriva",
+            ArtifactSystem::LinuxRedhat => "Red Hat",
+            ArtifactSystem::LinuxFedora => "Fedora",
+            ArtifactSystem::LinuxSuse => "SUSE",
+            ArtifactSystem::LinuxGentoo => "Gentoo",
+            ArtifactSystem::Ios => "iOS",
+            ArtifactSystem::Android => "Android",
+            ArtifactSystem::Freebsd => "FreeBSD",
+        }
+    }
+
+    /// The lowercase identifier for the system (same as the config/serialized form)
+    pub fn id(&self) -> &'static str {
+        match self {
+            ArtifactSystem::Windows => "windows",
+            ArtifactSystem::Windows64 => "windows64",
+            ArtifactSystem::WindowsArm => "windowsarm",
+            ArtifactSystem::Mac => "mac",
+            ArtifactSystem::MacPpc => "macppc",
+            ArtifactSystem::Mac32 => "mac32",
+            ArtifactSystem::MacSilicon => "macsilicon",
+            ArtifactSystem::Linux => "linux",
+            ArtifactSystem::LinuxUbuntu => "linuxubuntu",
+            ArtifactSystem::LinuxDebian => "linuxdebian",
+            ArtifactSystem::LinuxMandriva => "linuxmandriva",
+            ArtifactSystem::LinuxRedhat => "linuxredhat",
+            ArtifactSystem::LinuxFedora => "linuxfedora",
+            ArtifactSystem::LinuxSuse => "linuxsuse",
+            ArtifactSystem::LinuxGentoo => "linuxgentoo",
+            ArtifactSystem::Ios => "ios",
+            ArtifactSystem::Android => "android",
+            ArtifactSystem::Freebsd => "freebsd",
+        }
+    }
+}
+
 /// Info about downloadable artifacts / installers / package-managers (complete version)
 #[derive(Debug, Clone)]
 pub struct ArtifactsConfig {
     pub auto: bool,
     pub cargo_dist: bool,
     pub match_package_names: bool,
+    /// A pattern like `myapp-v{version}` that release tags must match
+    pub tag_pattern: Option<String>,
+    /// Whether the release artifacts have GitHub artifact attestations
+    pub github_attestations: bool,
+    /// The apps this site is for, if they were set explicitly
+    pub apps: Vec<String>,
+    /// The app we assume this site is for if `apps` isn't set (the package name)
+    pub default_app: Option<String>,
     pub package_managers: PackageManagersConfig,
+    /// Package managers we inferred from the project manifest
+    ///
+    /// These are only shown if nothing else provides an installer with the same name
+    pub detected_package_managers: PackageManagersConfig,
     pub hidden: Vec<String>,
 }
 
@@ -58,7 +169,9 @@ pub struct ArtifactsLayer {
     /// Artifact auto-detection currently includes the following concepts:
     ///
     /// * **platforms**: if an artifact name contains a [rust target triple][triple] then we will
-    ///   assume it's specific to that platform. Otherwise we will infer platform using extension
+    ///   assume it's specific to that platform. We also understand Python wheel platform tags
+    ///   ("manylinux_2_17_x86_64", "macosx_11_0_arm64", "win_amd64") and Go-style names
+    ///   ("linux_amd64"). Otherwise we will infer platform using extension
     ///   (with rules like "ps1 scripts are for windows, sh scripts are for unix").
     /// * **archives**: if a platform-specific artifact name ends with an archive format
     ///   (.tar.*, .zip .7z, .rar...) then we will assume it contains the binaries
@@ -68,8 +181,10 @@ pub struct ArtifactsLayer {
     ///   it (`irm | iex` for ps1)
     /// * **bundles**: if an artifact name ends with a known format for some kind of
     ///   installer/bundle we will recommend its download at a higher priority, assuming
-    ///   this is a very good way to install your app. This includes ".msi", ".app", ".dmg",
-    ///   ".deb", ".rpm", ".pkg.tar.*", ".flatpak", and ".snap".
+    ///   this is a very good way to install your app. This includes ".msi", ".msix", ".appx",
+    ///   setup ".exe"s, ".app", ".dmg", ".pkg", ".deb", ".rpm", ".pkg.tar.*", ".flatpak",
+    ///   ".snap", ".AppImage", and ".apk". Packages for other ecosystems (".nupkg", ".whl",
+    ///   ".vsix") are also detected, but recommended at a lower priority.
     ///
     /// [triple]: https://doc.rust-lang.org/nightly/rustc/platform-support.html
     pub auto: Option<bool>,
@@ -91,6 +206,36 @@ pub struct ArtifactsLayer {
     ///
     /// This defaults to false.
     pub match_package_names: Option<bool>,
+    /// A pattern that release tags must match, like "myapp-v{version}"
+    ///
+    /// Releases whose tags don't match are skipped, and the part matched by `{version}`
+    /// is used as the release's version. The latest stable release and latest prerelease
+    /// are then picked by comparing versions (as semver), instead of by publish date.
+    ///
+    /// If omitted, the version is taken from the tag with any leading "v" removed,
+    /// so tags like "1.2.3" and "v1.2.3" work.
+    pub tag_pattern: Option<String>,
+    /// Which apps in your Releases this site is for
+    ///
+    /// This is useful if you have a monorepo that ships several apps in the same Release,
+    /// and want each app's site to only show its own artifacts.
+    ///
+    /// If cargo-dist integration is enabled, these are the names of the apps in the
+    /// dist-manifest.json. Otherwise, only artifacts whose file names start with one of these
+    /// names (or contain it between `-`, `_` or `.` separators) are shown, along with files that
+    /// aren't for any particular app, like `install.sh` or `SHA256SUMS`.
+    ///
+    /// This defaults to your package's name. If nothing in a Release mentions any of these apps,
+    /// we assume the whole Release is for this site and don't filter anything.
+    pub apps: Option<Vec<String>>,
+    /// Whether your release artifacts have GitHub artifact attestations
+    ///
+    /// If enabled, the install page will explain how to verify downloads with
+    /// `gh attestation verify`.
+    ///
+    /// We default this to true if `github-attestations = true` is set in your
+    /// cargo-dist config.
+    pub github_attestations: Option<bool>,
     /// Snippets saying how to install your project using various package-managers
     ///
     /// These are grouped into "preferred" and "additional"
@@ -120,9 +265,33 @@ pub struct ArtifactsLayer {
     /// }
     /// ```
     ///
+    /// One-liners are assumed to work on every platform. If a package manager only works on
+    /// some of them, use an object instead, and the install widget will only offer it there:
+    ///
+    /// ```json
+    /// {
+    ///   "homebrew": {
+    ///     "command": "brew install axodotdev/tap/axolotlsay",
+    ///     "targets": ["mac", "linux"],
+    ///     "description": "Install from our Homebrew tap",
+    ///     "preference": "native"
+    ///   }
+    /// }
+    /// ```
+    ///
     /// If a package_manager has the same name as an auto-detected installer,
     /// it will overwrite the auto-detected result, allowing you to specify something
     /// more preferrable (relevant to cargo-dist npm packages).
+    ///
+    /// Some entries are automatically suggested based on your project manifest:
+    ///
+    /// * Cargo packages with binaries get a preferred "cargo" (`cargo install <name> --locked`),
+    ///   and an additional "binstall" if `[package.metadata.binstall]` is set
+    /// * npm packages with binaries get a preferred "npm" (`npm install -g <name>`)
+    ///   and an additional "npx" (`npx <name>`)
+    ///
+    /// These only show up if something else enables the install page, and any entry here
+    /// with the same name replaces them. To remove one outright, add it to `hidden`.
     pub package_managers: Option<PackageManagersLayer>,
     /// Artifact/installer listings to supress from the install widget's tabs
     /// and the install page's package-manager/script listings.
@@ -139,11 +308,27 @@ impl Default for ArtifactsConfig {
             auto: false,
             cargo_dist: false,
             match_package_names: false,
+            tag_pattern: None,
+            github_attestations: false,
+            apps: vec![],
+            default_app: None,
             package_managers: PackageManagersConfig::default(),
+            detected_package_managers: PackageManagersConfig::default(),
             hidden: vec![],
         }
     }
 }
+impl ArtifactsConfig {
+    /// The apps this site is for: `apps` if it was set, otherwise the package name
+    pub fn shown_apps(&self) -> Vec<String> {
+        if self.apps.is_empty() {
+            self.default_app.iter().cloned().collect()
+        } else {
+            self.apps.clone()
+        }
+    }
+}
+
 impl ApplyLayer for ArtifactsConfig {
     type Layer = ArtifactsLayer;
     fn apply_layer(&mut self, layer: Self::Layer) {
@@ -152,6 +337,9 @@ impl ApplyLayer for ArtifactsConfig {
             auto,
             cargo_dist,
             match_package_names,
+            tag_pattern,
+            apps,
+            github_attestations,
             package_managers,
             hidden,
         } = layer;
@@ -159,6 +347,9 @@ impl ApplyLayer for ArtifactsConfig {
         self.auto.apply_val(auto);
         self.cargo_dist.apply_val(cargo_dist);
         self.match_package_names.apply_val(match_package_names);
+        self.tag_pattern.apply_opt(tag_pattern);
+        self.apps.apply_val(apps);
+        self.github_attestations.apply_val(github_attestations);
         self.package_managers.apply_val_layer(package_managers);
         // In the future this might want to be `extend`
         self.hidden.apply_val(hidden);
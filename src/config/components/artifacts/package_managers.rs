@@ -7,8 +7,8 @@ use crate::config::{ApplyLayer, ApplyValExt};
 /// Package managers to display (complete version)
 #[derive(Debug, Clone)]
 pub struct PackageManagersConfig {
-    pub preferred: IndexMap<String, String>,
-    pub additional: IndexMap<String, String>,
+    pub preferred: IndexMap<String, PackageManager>,
+    pub additional: IndexMap<String, PackageManager>,
 }
 /// Package managers to display
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -17,11 +17,77 @@ pub struct PackageManagersLayer {
     /// Packages to display in both the install widget and install page
     ///
     /// See docs for the parent "package_managers" field for details
-    pub preferred: Option<IndexMap<String, String>>,
+    pub preferred: Option<IndexMap<String, PackageManagerLayer>>,
     /// Packages to display in just the install page
     ///
     /// See docs for the parent "package_managers" field for details
-    pub additional: Option<IndexMap<String, String>>,
+    pub additional: Option<IndexMap<String, PackageManagerLayer>>,
+}
+
+/// How much an installer should be preferred (descending order)
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallerPreference {
+    /// The best way
+    Preferred,
+    /// Some kind of "native" installer like a .msi or .dmg
+    Native,
+    /// Some kind of curl|sh script
+    Script,
+    /// Any kind of custom/misc/unknown solution
+    Custom,
+    /// Just a tarball containing the binary
+    Archive,
+}
+
+/// A single package manager entry (complete version)
+#[derive(Debug, Clone)]
+pub struct PackageManager {
+    /// The one-liner to run
+    pub command: String,
+    /// Target triples and/or OS families this works on (empty means "everywhere")
+    pub targets: Vec<String>,
+    /// A longer description of the package manager
+    pub description: Option<String>,
+    /// How much to prefer this over other installers
+    pub preference: InstallerPreference,
+}
+
+/// A package manager entry
+///
+/// This can either be just the one-liner to run, or an object with more details
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PackageManagerLayer {
+    /// The one-liner to run, which is assumed to work on every platform
+    Command(String),
+    /// A more detailed entry
+    Full(PackageManagerFullLayer),
+}
+
+/// A package manager entry with more details
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManagerFullLayer {
+    /// The one-liner to run
+    pub command: String,
+    /// Where this package manager works
+    ///
+    /// Each entry can be a target triple ("x86_64-pc-windows-msvc"), an OS family
+    /// ("windows", "mac", "linux", "unix"), a more specific system ("windows64", "freebsd", ...),
+    /// or a Linux distro ("linuxdebian", "linuxfedora", ...).
+    ///
+    /// If omitted, the package manager is assumed to work everywhere.
+    pub targets: Option<Vec<String>>,
+    /// A longer description of the package manager
+    pub description: Option<String>,
+    /// How much to prefer this over other ways to install on the same platform
+    ///
+    /// One of "preferred", "native", "script", "custom", or "archive"
+    /// (from most to least preferred). Defaults to "custom".
+    pub preference: Option<InstallerPreference>,
 }
 
 impl Default for PackageManagersConfig {
@@ -41,8 +107,10 @@ impl ApplyLayer for PackageManagersConfig {
             additional,
         } = layer;
         // In the future these might want to be `extend`
-        self.preferred.apply_val(preferred);
-        self.additional.apply_val(additional);
+        self.preferred
+            .apply_val(preferred.map(PackageManager::from_layers));
+        self.additional
+            .apply_val(additional.map(PackageManager::from_layers));
     }
 }
 
@@ -57,3 +125,43 @@ impl PackageManagersConfig {
         self.preferred.is_empty() && self.additional.is_empty()
     }
 }
+
+impl PackageManager {
+    /// Make an entry that works everywhere
+    pub fn new(command: String) -> Self {
+        PackageManager {
+            command,
+            targets: vec![],
+            description: None,
+            preference: InstallerPreference::Custom,
+        }
+    }
+
+    fn from_layers(
+        layers: IndexMap<String, PackageManagerLayer>,
+    ) -> IndexMap<String, PackageManager> {
+        layers
+            .into_iter()
+            .map(|(label, layer)| (label, PackageManager::from(layer)))
+            .collect()
+    }
+}
+
+impl From<PackageManagerLayer> for PackageManager {
+    fn from(layer: PackageManagerLayer) -> Self {
+        match layer {
+            PackageManagerLayer::Command(command) => PackageManager::new(command),
+            PackageManagerLayer::Full(PackageManagerFullLayer {
+                command,
+                targets,
+                description,
+                preference,
+            }) => PackageManager {
+                command,
+                targets: targets.unwrap_or_default(),
+                description,
+                preference: preference.unwrap_or(InstallerPreference::Custom),
+            },
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{ApplyLayer, ApplyValExt};
+use crate::config::{ApplyLayer, ApplyOptExt, ApplyValExt};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +9,28 @@ pub struct ChangelogConfig {
     pub read_changelog_file: bool,
     /// Whether to generate a RSS file
     pub rss_feed: bool,
+    /// Whether to generate an Atom file
+    pub atom_feed: bool,
+    /// Whether to generate a JSON Feed file
+    pub json_feed: bool,
+    /// How many releases to put in feeds (None for all of them)
+    pub feed_limit: Option<usize>,
+    /// Path to the changelog file (if None, we look for one in the current directory)
+    pub path: Option<String>,
+    /// Where the entries of the changelog come from
+    pub mode: ChangelogMode,
+    /// Whether to link each release to a comparison with the one before it
+    pub compare_links: bool,
+    /// Whether to turn issue references, mentions and commit SHAs into links
+    pub autolinks: bool,
+    /// Whether to split release notes into Keep a Changelog categories
+    pub categories: bool,
+    /// Whether to list the people who made commits for each release
+    pub contributors: bool,
+    /// How many releases to show on each page of the changelog (None for all of them)
+    pub per_page: Option<usize>,
+    /// Whether to group the side nav by version line
+    pub group_nav: bool,
 }
 
 /// The config for generating a separate changelog page
@@ -20,6 +42,59 @@ pub struct ChangelogLayer {
     pub read_changelog_file: Option<bool>,
     /// Whether to generate a RSS file under `changelog.rss`.
     pub rss_feed: Option<bool>,
+    /// Whether to generate an Atom file under `changelog.atom`.
+    pub atom_feed: Option<bool>,
+    /// Whether to generate a JSON Feed (version 1.1) file under `changelog.json`.
+    pub json_feed: Option<bool>,
+    /// How many of the newest releases to put in feeds.
+    ///
+    /// By default, every release is included.
+    pub feed_limit: Option<usize>,
+    /// The path to your changelog file.
+    ///
+    /// If this isn't set, we look for `CHANGELOG.md`, `CHANGES.md` or `HISTORY.md`
+    /// (or the same without the `.md`) in the current directory.
+    pub path: Option<String>,
+    /// Where the entries of the changelog come from.
+    ///
+    /// - "releases": Make an entry for each release we find (on GitHub, in git tags, etc.),
+    ///   with its notes from the matching section of your changelog file.
+    /// - "file": Make an entry for each version heading in your changelog file,
+    ///   whether or not there's a release for it.
+    pub mode: Option<ChangelogMode>,
+    /// Whether to link each release to a comparison with the release before it
+    /// on your repository's forge (GitHub, GitLab, Forgejo).
+    pub compare_links: Option<bool>,
+    /// Whether to turn issue references (`#123`, `GH-123`), mentions (`@user`) and
+    /// commit SHAs in release notes into links to your repository.
+    ///
+    /// Off by default, since it changes how existing release notes render.
+    pub autolinks: Option<bool>,
+    /// Whether to show the sections of release notes that follow Keep a Changelog
+    /// (`### Added`, `### Fixed`, `### Security` and so on) with badges for each category.
+    pub categories: Option<bool>,
+    /// Whether to list the people who made commits between each release and the one
+    /// before it, read from your local git history.
+    pub contributors: Option<bool>,
+    /// How many releases to show on each page of the changelog.
+    ///
+    /// The first page is at `changelog/`, and the rest are at `changelog/page/2/` and so on.
+    /// By default, every release is on one page.
+    pub per_page: Option<usize>,
+    /// Whether to group the releases in the side nav by their major and minor version
+    /// (like "1.2"), in collapsible sections.
+    pub group_nav: Option<bool>,
+}
+
+/// Where the entries of the changelog come from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangelogMode {
+    /// Make an entry for each release
+    #[default]
+    Releases,
+    /// Make an entry for each version heading in the changelog file
+    File,
 }
 
 impl Default for ChangelogConfig {
@@ -27,6 +102,17 @@ impl Default for ChangelogConfig {
         ChangelogConfig {
             read_changelog_file: true,
             rss_feed: true,
+            atom_feed: false,
+            json_feed: false,
+            feed_limit: None,
+            path: None,
+            mode: ChangelogMode::Releases,
+            compare_links: true,
+            autolinks: false,
+            categories: true,
+            contributors: false,
+            per_page: None,
+            group_nav: false,
         }
     }
 }
@@ -38,8 +124,30 @@ impl ApplyLayer for ChangelogConfig {
         let ChangelogLayer {
             read_changelog_file,
             rss_feed,
+            atom_feed,
+            json_feed,
+            feed_limit,
+            path,
+            mode,
+            compare_links,
+            autolinks,
+            categories,
+            contributors,
+            per_page,
+            group_nav,
         } = layer;
         self.read_changelog_file.apply_val(read_changelog_file);
         self.rss_feed.apply_val(rss_feed);
+        self.atom_feed.apply_val(atom_feed);
+        self.json_feed.apply_val(json_feed);
+        self.feed_limit.apply_opt(feed_limit);
+        self.path.apply_opt(path);
+        self.mode.apply_val(mode);
+        self.compare_links.apply_val(compare_links);
+        self.autolinks.apply_val(autolinks);
+        self.categories.apply_val(categories);
+        self.contributors.apply_val(contributors);
+        self.per_page.apply_opt(per_page);
+        self.group_nav.apply_val(group_nav);
     }
 }
@@ -0,0 +1,46 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ApplyLayer, ApplyOptExt};
+
+/// Config for fetching releases from a self-hostable forge like GitLab (complete version)
+#[derive(Debug, Clone)]
+pub struct ForgeConfig {
+    pub base_url: Option<String>,
+    pub token_env: Option<String>,
+}
+/// Settings for fetching releases from a self-hostable forge like GitLab
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForgeLayer {
+    /// The URL of the instance to fetch releases from (e.g. "https://gitlab.example.com")
+    ///
+    /// Defaults to the host of `project.repository`, so this is only needed if
+    /// the instance's API lives somewhere else.
+    pub base_url: Option<String>,
+    /// The environment variable containing an access token to use for API requests
+    ///
+    /// This is only needed for private projects, or to avoid rate limits.
+    pub token_env: Option<String>,
+}
+
+impl Default for ForgeConfig {
+    fn default() -> Self {
+        ForgeConfig {
+            base_url: None,
+            token_env: None,
+        }
+    }
+}
+impl ApplyLayer for ForgeConfig {
+    type Layer = ForgeLayer;
+    fn apply_layer(&mut self, layer: Self::Layer) {
+        // This is intentionally written slightly cumbersome to make you update this
+        let ForgeLayer {
+            base_url,
+            token_env,
+        } = layer;
+        self.base_url.apply_opt(base_url);
+        self.token_env.apply_opt(token_env);
+    }
+}
@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ApplyLayer, ApplyOptExt, ApplyValExt};
+
+/// Config for reading releases from local directories (complete version)
+#[derive(Debug, Clone)]
+pub struct LocalReleasesConfig {
+    pub paths: Vec<String>,
+    pub download_url: Option<String>,
+}
+/// Settings for reading releases from local directories
+///
+/// These are only used if `components.source` is set to "local".
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LocalReleasesLayer {
+    /// Directories to read releases from
+    ///
+    /// Each directory can either be a single release (containing a dist-manifest.json
+    /// and/or the release's assets, like cargo-dist's "target/distrib/"), or contain one
+    /// subdirectory per release, named after the release's tag.
+    ///
+    /// Defaults to "target/distrib".
+    pub paths: Option<Vec<String>>,
+    /// A template for the URL each file will be downloadable from once it's published
+    ///
+    /// "{tag}", "{version}" and "{file}" will be replaced with the release's tag, the release's
+    /// version (the tag without a leading "v"), and the name of the file.
+    ///
+    /// Defaults to the file's GitHub Release download URL if `project.repository` is a GitHub repo,
+    /// and otherwise links to the file on your machine.
+    pub download_url: Option<String>,
+}
+
+impl Default for LocalReleasesConfig {
+    fn default() -> Self {
+        LocalReleasesConfig {
+            paths: vec!["target/distrib".to_owned()],
+            download_url: None,
+        }
+    }
+}
+impl ApplyLayer for LocalReleasesConfig {
+    type Layer = LocalReleasesLayer;
+    fn apply_layer(&mut self, layer: Self::Layer) {
+        // This is intentionally written slightly cumbersome to make you update this
+        let LocalReleasesLayer {
+            paths,
+            download_url,
+        } = layer;
+        self.paths.apply_val(paths);
+        self.download_url.apply_opt(download_url);
+    }
+}
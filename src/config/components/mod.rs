@@ -3,12 +3,19 @@ use serde::{Deserialize, Serialize};
 
 mod artifacts;
 mod changelog;
+mod forge;
 mod funding;
+mod local_releases;
 mod mdbooks;
 
-use crate::config::components::changelog::{ChangelogConfig, ChangelogLayer};
-pub use artifacts::{ArtifactsConfig, ArtifactsLayer, PackageManagersConfig, PackageManagersLayer};
+pub use artifacts::{
+    ArtifactSystem, ArtifactsConfig, ArtifactsLayer, InstallerPreference, PackageManager,
+    PackageManagerFullLayer, PackageManagerLayer, PackageManagersConfig, PackageManagersLayer,
+};
+pub use changelog::{ChangelogConfig, ChangelogLayer, ChangelogMode};
+pub use forge::{ForgeConfig, ForgeLayer};
 pub use funding::{FundingConfig, FundingLayer};
+pub use local_releases::{LocalReleasesConfig, LocalReleasesLayer};
 pub use mdbooks::{MdBookConfig, MdBookLayer};
 
 use super::{ApplyBoolLayerExt, ApplyLayer, ApplyOptExt, BoolOr};
@@ -39,7 +46,17 @@ pub struct ComponentConfig {
     /// manually disables it.
     pub artifacts: Option<ArtifactsConfig>,
     /// Which source to fetch release data from.
+    ///
+    /// If None, this is autodetected from the repository URL (see [`ComponentConfig::releases_source`][]).
     pub source: Option<ReleasesSource>,
+    /// Where to find releases if `source` is "local"
+    pub local_releases: LocalReleasesConfig,
+    /// How to reach GitHub if `source` is "github"
+    pub github: ForgeConfig,
+    /// How to reach GitLab if `source` is "gitlab"
+    pub gitlab: ForgeConfig,
+    /// How to reach Forgejo/Gitea if `source` is "forgejo"
+    pub forgejo: ForgeConfig,
 }
 /// Extra components
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -175,20 +192,57 @@ pub struct ComponentLayer {
     pub artifacts: Option<BoolOr<ArtifactsLayer>>,
     /// Where we should attempt to fetch release data from.
     pub source: Option<ReleasesSource>,
+    /// Where to find releases if `source` is "local"
+    ///
+    /// See the docs for the inner fields for details.
+    pub local_releases: Option<LocalReleasesLayer>,
+    /// How to reach GitHub if `source` is "github"
+    ///
+    /// This is only needed for GitHub Enterprise Server instances whose API isn't on the
+    /// same host as `project.repository`, or for private repositories.
+    pub github: Option<ForgeLayer>,
+    /// How to reach GitLab if `source` is "gitlab"
+    ///
+    /// This is only needed for self-hosted instances whose API isn't on the same host
+    /// as `project.repository`, or for private projects.
+    pub gitlab: Option<ForgeLayer>,
+    /// How to reach Forgejo (or Gitea) if `source` is "forgejo"
+    ///
+    /// This is only needed if the instance's API isn't on the same host
+    /// as `project.repository`, or for private repositories.
+    pub forgejo: Option<ForgeLayer>,
 }
 
 /// Denotes a host for releases. Historically, this has only been GitHub, but we now also
 /// support alternative providers.
 ///
+/// If this isn't set, it's autodetected from `project.repository` (GitLab URLs use "gitlab",
+/// Codeberg/Forgejo/Gitea URLs use "forgejo", everything else uses "github"). Without a
+/// repository, "git" is used.
+///
 /// - "github": Attempt to fetch releases from the GitHub repository the user set in their
-///   configuration.
+///   configuration. GitHub Enterprise Server is supported (see `components.github`).
+/// - "gitlab": Attempt to fetch releases from the GitLab project the user set in their
+///   configuration. Self-hosted instances are supported (see `components.gitlab`).
+/// - "forgejo" (or "gitea"): Attempt to fetch releases from the Forgejo or Gitea repository
+///   the user set in their configuration, e.g. on Codeberg (see `components.forgejo`).
 /// - "axodotdev": Attempt to fetch from axo Releases, using the GitHub repository the user
 ///   has set in their configuration as the project name.
+/// - "local": Read releases from directories on disk (see `components.local_releases`),
+///   like the "target/distrib/" directory cargo-dist builds. No network access is needed.
+/// - "git": Make a release for each version tag in the local git repository, with the tag's
+///   message (or the matching section of your CHANGELOG.md) as its notes. No network access
+///   is needed. Hosted sources also fall back to this if they have no releases.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ReleasesSource {
     GitHub,
+    GitLab,
+    #[serde(alias = "gitea")]
+    Forgejo,
     Axodotdev,
+    Local,
+    Git,
 }
 
 impl Default for ComponentConfig {
@@ -198,7 +252,11 @@ impl Default for ComponentConfig {
             mdbook: Some(MdBookConfig::default()),
             funding: Some(FundingConfig::default()),
             artifacts: Some(ArtifactsConfig::default()),
-            source: Some(ReleasesSource::GitHub),
+            source: None,
+            local_releases: LocalReleasesConfig::default(),
+            github: ForgeConfig::default(),
+            gitlab: ForgeConfig::default(),
+            forgejo: ForgeConfig::default(),
         }
     }
 }
@@ -212,15 +270,54 @@ impl ApplyLayer for ComponentConfig {
             funding,
             artifacts,
             source,
+            local_releases,
+            github,
+            gitlab,
+            forgejo,
         } = layer;
         self.changelog.apply_bool_layer(changelog);
         self.mdbook.apply_bool_layer(mdbook);
         self.funding.apply_bool_layer(funding);
         self.artifacts.apply_bool_layer(artifacts);
         self.source.apply_opt(source);
+        self.local_releases.apply_val_layer(local_releases);
+        self.github.apply_val_layer(github);
+        self.gitlab.apply_val_layer(gitlab);
+        self.forgejo.apply_val_layer(forgejo);
     }
 }
 impl ComponentConfig {
+    /// Which source to fetch releases from
+    ///
+    /// If the user didn't pick one, this is autodetected from the repository URL.
+    pub fn releases_source(&self, repo_url: Option<&str>) -> ReleasesSource {
+        if let Some(source) = &self.source {
+            return source.clone();
+        }
+        let Some(repo_url) = repo_url else {
+            return ReleasesSource::Git;
+        };
+        let host = repo_host(repo_url).unwrap_or_default();
+        let host_parts = host.split('.').collect::<Vec<_>>();
+        if host_parts.contains(&"gitlab") {
+            ReleasesSource::GitLab
+        } else if ["codeberg", "forgejo", "gitea"]
+            .iter()
+            .any(|forge| host_parts.contains(forge))
+        {
+            ReleasesSource::Forgejo
+        } else {
+            ReleasesSource::GitHub
+        }
+    }
+
+    /// Whether the changelog is made from releases (and so needs them fetched)
+    pub fn changelog_from_releases(&self) -> bool {
+        self.changelog
+            .as_ref()
+            .is_some_and(|c| c.mode == ChangelogMode::Releases)
+    }
+
     /// Convenience for checking if the artifacts component is actually enabled
     /// because a ton of code was repeating this due to the extra Option.
     pub fn artifacts_enabled(&self) -> bool {
@@ -230,3 +327,15 @@ impl ComponentConfig {
             .unwrap_or(false)
     }
 }
+
+/// Get the host of a repository URL, which can be https or ssh (`git@host:owner/name.git`)
+pub(crate) fn repo_host(repo_url: &str) -> Option<String> {
+    if let Some(rest) = repo_url.strip_prefix("git@") {
+        return rest.split_once(':').map(|(host, _)| host.to_owned());
+    }
+    let repo_url = repo_url.strip_prefix("git+").unwrap_or(repo_url);
+    url::Url::parse(repo_url)
+        .ok()?
+        .host_str()
+        .map(|h| h.to_owned())
+}
@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ApplyLayer, ApplyOptExt, ApplyValExt};
+
+/// An announcement banner at the top of every page (complete version)
+#[derive(Debug, Serialize, Clone)]
+pub struct AnnouncementConfig {
+    /// What to announce (markdown)
+    pub text: String,
+    /// The last day to show the announcement on (YYYY-MM-DD)
+    pub expires: Option<String>,
+    /// Whether visitors can close the banner
+    pub dismissible: bool,
+}
+/// An announcement banner at the top of every page
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnnouncementLayer {
+    /// What to announce, as markdown (so it can have links)
+    pub text: Option<String>,
+    /// The last day to show the announcement on, like "2024-05-01"
+    ///
+    /// Builds after this day leave the banner out, and pages built before it
+    /// hide it once the day has passed.
+    pub expires: Option<String>,
+    /// Whether visitors can close the banner (it stays closed until the text changes)
+    ///
+    /// This is true by default.
+    pub dismissible: Option<bool>,
+}
+
+impl Default for AnnouncementConfig {
+    fn default() -> Self {
+        AnnouncementConfig {
+            text: String::new(),
+            expires: None,
+            dismissible: true,
+        }
+    }
+}
+impl ApplyLayer for AnnouncementConfig {
+    type Layer = AnnouncementLayer;
+    fn apply_layer(&mut self, layer: Self::Layer) {
+        // This is intentionally written slightly cumbersome to make you update this
+        let AnnouncementLayer {
+            text,
+            expires,
+            dismissible,
+        } = layer;
+        self.text.apply_val(text);
+        self.expires.apply_opt(expires);
+        self.dismissible.apply_val(dismissible);
+    }
+}
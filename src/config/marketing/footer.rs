@@ -0,0 +1,52 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ApplyLayer, ApplyOptExt, ApplyValExt};
+
+/// What's in the footer of every page (complete version)
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct FooterConfig {
+    /// Markdown to show instead of the project name and license
+    pub text: Option<String>,
+    /// Columns of links
+    pub columns: Vec<FooterColumn>,
+}
+/// What's in the footer of every page
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FooterLayer {
+    /// Markdown to show instead of the project name and license
+    pub text: Option<String>,
+    /// Columns of links, each with a title
+    pub columns: Option<Vec<FooterColumn>>,
+}
+
+/// A column of links in the footer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FooterColumn {
+    /// The heading of the column
+    pub title: String,
+    /// The links in the column, in order
+    pub links: Vec<FooterLink>,
+}
+
+/// A link in the footer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FooterLink {
+    /// The text of the link
+    pub label: String,
+    /// Where the link goes
+    pub url: String,
+}
+
+impl ApplyLayer for FooterConfig {
+    type Layer = FooterLayer;
+    fn apply_layer(&mut self, layer: Self::Layer) {
+        // This is intentionally written slightly cumbersome to make you update this
+        let FooterLayer { text, columns } = layer;
+        self.text.apply_opt(text);
+        self.columns.apply_val(columns);
+    }
+}
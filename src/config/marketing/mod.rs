@@ -1,11 +1,17 @@
 pub use analytics::AnalyticsConfig;
+pub use announcement::{AnnouncementConfig, AnnouncementLayer};
+pub use footer::{FooterColumn, FooterConfig, FooterLayer, FooterLink};
+pub use repo_banner::{RepoBannerConfig, RepoBannerLayer, RepoForge};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 pub use social::{SocialConfig, SocialLayer};
 
-use super::ApplyLayer;
+use super::{ApplyBoolLayerExt, ApplyLayer, BoolOr};
 
 mod analytics;
+mod announcement;
+mod footer;
+mod repo_banner;
 mod social;
 
 /// Marketing config (complete version)
@@ -15,6 +21,12 @@ pub struct MarketingConfig {
     pub analytics: Option<AnalyticsConfig>,
     /// Social media
     pub social: SocialConfig,
+    /// The announcement banner
+    pub announcement: Option<AnnouncementConfig>,
+    /// The footer
+    pub footer: FooterConfig,
+    /// The link to the repository at the top of the page
+    pub repo_banner: Option<RepoBannerConfig>,
 }
 /// Settings for marketing/social/analytics
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -26,6 +38,14 @@ pub struct MarketingLayer {
     pub analytics: Option<AnalyticsConfig>,
     /// Settings for social media integrations
     pub social: Option<SocialLayer>,
+    /// An announcement banner to show at the top of every page
+    pub announcement: Option<BoolOr<AnnouncementLayer>>,
+    /// What to show in the footer of every page
+    pub footer: Option<FooterLayer>,
+    /// Settings for the link to your repository at the top of every page
+    ///
+    /// Set this to false to hide it. It's only shown if `project.repository` is set.
+    pub repo_banner: Option<BoolOr<RepoBannerLayer>>,
 }
 
 impl Default for MarketingConfig {
@@ -33,6 +53,9 @@ impl Default for MarketingConfig {
         MarketingConfig {
             analytics: None,
             social: SocialConfig::default(),
+            announcement: None,
+            footer: FooterConfig::default(),
+            repo_banner: Some(RepoBannerConfig::default()),
         }
     }
 }
@@ -40,7 +63,13 @@ impl ApplyLayer for MarketingConfig {
     type Layer = MarketingLayer;
     fn apply_layer(&mut self, layer: Self::Layer) {
         // This is intentionally written slightly cumbersome to make you update this
-        let MarketingLayer { analytics, social } = layer;
+        let MarketingLayer {
+            analytics,
+            social,
+            announcement,
+            footer,
+            repo_banner,
+        } = layer;
 
         // FIXME: this is kinda goofy but there's not an obvious thing to do
         // if we need to change the enum variant and we care about preserving things.
@@ -49,5 +78,8 @@ impl ApplyLayer for MarketingConfig {
             self.analytics = Some(analytics);
         }
         self.social.apply_val_layer(social);
+        self.announcement.apply_bool_layer(announcement);
+        self.footer.apply_val_layer(footer);
+        self.repo_banner.apply_bool_layer(repo_banner);
     }
 }
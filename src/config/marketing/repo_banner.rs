@@ -0,0 +1,86 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::components::repo_host;
+use crate::config::{ApplyLayer, ApplyOptExt, ReleasesSource};
+
+/// The link to the repository at the top of every page (complete version)
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RepoBannerConfig {
+    /// The text of the link (by default, based on the forge)
+    pub text: Option<String>,
+    /// Path or URL to an image to show instead of the forge's icon
+    pub icon: Option<String>,
+    /// Where the repository is hosted (by default, detected from the repository URL)
+    pub forge: Option<RepoForge>,
+}
+/// The link to your repository at the top of every page
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RepoBannerLayer {
+    /// The text of the link
+    ///
+    /// By default this is "Check out our GitHub!" (or GitLab, or Codeberg...)
+    pub text: Option<String>,
+    /// Path or URL to an image to show instead of the forge's icon
+    pub icon: Option<String>,
+    /// Where your repository is hosted, for the icon and the default text
+    ///
+    /// By default, this is detected from your repository URL.
+    pub forge: Option<RepoForge>,
+}
+
+/// A site that hosts repositories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoForge {
+    GitHub,
+    GitLab,
+    Codeberg,
+    Forgejo,
+    Gitea,
+    /// Anywhere else
+    Other,
+}
+
+impl RepoForge {
+    /// Work out where a repository is hosted, from its URL and where we get releases from
+    pub fn detect(repo_url: &str, source: Option<&ReleasesSource>) -> Self {
+        let host = repo_host(repo_url).unwrap_or_default();
+        let host_parts = host.split('.').collect::<Vec<_>>();
+        let from_host = ["github", "gitlab", "codeberg", "forgejo", "gitea"]
+            .into_iter()
+            .find(|forge| host_parts.contains(forge));
+        match (source, from_host) {
+            (Some(ReleasesSource::GitHub), _) | (_, Some("github")) => RepoForge::GitHub,
+            (Some(ReleasesSource::GitLab), _) | (_, Some("gitlab")) => RepoForge::GitLab,
+            (_, Some("codeberg")) => RepoForge::Codeberg,
+            (_, Some("gitea")) => RepoForge::Gitea,
+            (Some(ReleasesSource::Forgejo), _) | (_, Some("forgejo")) => RepoForge::Forgejo,
+            _ => RepoForge::Other,
+        }
+    }
+
+    /// The name to show for the forge
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RepoForge::GitHub => "GitHub",
+            RepoForge::GitLab => "GitLab",
+            RepoForge::Codeberg => "Codeberg",
+            RepoForge::Forgejo => "Forgejo",
+            RepoForge::Gitea => "Gitea",
+            RepoForge::Other => "repository",
+        }
+    }
+}
+
+impl ApplyLayer for RepoBannerConfig {
+    type Layer = RepoBannerLayer;
+    fn apply_layer(&mut self, layer: Self::Layer) {
+        // This is intentionally written slightly cumbersome to make you update this
+        let RepoBannerLayer { text, icon, forge } = layer;
+        self.text.apply_opt(text);
+        self.icon.apply_opt(icon);
+        self.forge.apply_opt(forge);
+    }
+}
@@ -97,12 +97,20 @@ mod workspace;
 
 pub use self::axoproject::AxoprojectLayer;
 pub use self::oranda_config::OrandaLayer;
-pub use builds::{BuildConfig, BuildLayer};
+pub use builds::{BuildConfig, BuildLayer, NavBuiltin, NavItem};
+pub(crate) use components::repo_host;
 pub use components::{
-    ArtifactsConfig, ArtifactsLayer, ComponentConfig, ComponentLayer, FundingConfig, FundingLayer,
-    MdBookConfig, MdBookLayer, PackageManagersConfig, PackageManagersLayer, ReleasesSource,
+    ArtifactSystem, ArtifactsConfig, ArtifactsLayer, ChangelogConfig, ChangelogLayer,
+    ChangelogMode, ComponentConfig, ComponentLayer, ForgeConfig, ForgeLayer, FundingConfig,
+    FundingLayer, InstallerPreference, LocalReleasesConfig, LocalReleasesLayer, MdBookConfig,
+    MdBookLayer, PackageManager, PackageManagerFullLayer, PackageManagerLayer,
+    PackageManagersConfig, PackageManagersLayer, ReleasesSource,
+};
+pub use marketing::{
+    AnalyticsConfig, AnnouncementConfig, AnnouncementLayer, FooterColumn, FooterConfig,
+    FooterLayer, FooterLink, MarketingConfig, MarketingLayer, RepoBannerConfig, RepoBannerLayer,
+    RepoForge, SocialConfig, SocialLayer,
 };
-pub use marketing::{AnalyticsConfig, MarketingConfig, MarketingLayer, SocialConfig, SocialLayer};
 pub use workspace::{WorkspaceConfig, WorkspaceLayer, WorkspaceMember};
 
 pub use project::{ProjectConfig, ProjectLayer};
@@ -225,12 +233,21 @@ impl Config {
             let AxoprojectLayer {
                 project,
                 cargo_dist,
+                github_attestations,
+                package_managers,
                 members: _,
             } = layer;
 
+            // The package name is the default app name, so grab it before we move the layer
+            let package_name = project.as_ref().and_then(|p| p.name.clone());
             self.project.apply_val_layer(project);
             if let Some(artifacts) = &mut self.components.artifacts {
+                artifacts.default_app.apply_opt(package_name);
                 artifacts.cargo_dist.apply_val(cargo_dist);
+                artifacts.github_attestations.apply_val(github_attestations);
+                artifacts
+                    .detected_package_managers
+                    .apply_val(package_managers);
             }
         }
     }
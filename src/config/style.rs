@@ -2,7 +2,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::config::{ApplyLayer, ApplyOptExt};
-use crate::site::{markdown::SyntaxTheme, oranda_theme::OrandaTheme};
+use crate::site::markdown::SyntaxTheme;
+use crate::site::oranda_theme::{ColorScheme, CustomTheme, OrandaTheme};
 
 use super::ApplyValExt;
 
@@ -12,6 +13,8 @@ pub const ORANDA_CSS_TAG: &str = "v0.6.1";
 #[derive(Debug, Clone)]
 pub struct StyleConfig {
     pub theme: OrandaTheme,
+    pub color_scheme: ColorScheme,
+    pub custom_theme: Option<CustomTheme>,
     pub syntax_theme: SyntaxTheme,
     pub additional_css: Vec<String>,
     pub oranda_css_version: String,
@@ -29,6 +32,20 @@ pub struct StyleLayer {
     ///
     /// Default is "dark"
     pub theme: Option<OrandaTheme>,
+    /// Whether to follow the visitor's light/dark preference
+    ///
+    /// With "auto", pages use `theme` or its twin ("dark" and "light", or "axo_dark" and
+    /// "axo_light") depending on the visitor's system settings, and get a button to switch
+    /// between them. The choice is remembered, and shared with oranda's mdbook theme.
+    /// Themes without a twin are always used as they are.
+    ///
+    /// Default is "fixed"
+    pub color_scheme: Option<ColorScheme>,
+    /// Your own colors and fonts, on top of `theme`
+    ///
+    /// These also restyle your mdbook, if oranda is styling it. They're the same in light
+    /// and dark mode.
+    pub custom_theme: Option<CustomTheme>,
     /// The builtin syntax highlighting theme to use for all your pages
     ///
     /// WARNING: this feature is currently non-functional, only the default works!
@@ -53,6 +70,8 @@ impl Default for StyleConfig {
     fn default() -> Self {
         StyleConfig {
             theme: OrandaTheme::Dark,
+            color_scheme: ColorScheme::Fixed,
+            custom_theme: None,
             syntax_theme: SyntaxTheme::MaterialTheme,
             additional_css: vec![],
             oranda_css_version: ORANDA_CSS_TAG.to_owned(),
@@ -67,6 +86,8 @@ impl ApplyLayer for StyleConfig {
         // This is intentionally written slightly cumbersome to make you update this
         let StyleLayer {
             theme,
+            color_scheme,
+            custom_theme,
             syntax_theme,
             additional_css,
             oranda_css_version,
@@ -75,6 +96,8 @@ impl ApplyLayer for StyleConfig {
         } = layer;
 
         self.theme.apply_val(theme);
+        self.color_scheme.apply_val(color_scheme);
+        self.custom_theme.apply_opt(custom_theme);
         self.syntax_theme.apply_val(syntax_theme);
         self.oranda_css_version.apply_val(oranda_css_version);
         // In the future this might want to be `extend`
@@ -49,13 +49,32 @@ const EXT_BUNDLE_DMG: &str = ".dmg";
 const EXT_BUNDLE_DEB: &str = ".deb";
 const EXT_BUNDLE_RPM: &str = ".rpm";
 // annoying subtlety: pacman (arch linux) uses .pkg.tar.* files,
-// so we need to use "contains" instead of "ends_with" for bundles
+// so we need to use "contains" instead of "ends_with" for it (see `has_bundle_ext`)
 const EXT_BUNDLE_PACMAN: &str = ".pkg.tar.";
 const EXT_BUNDLE_FLATPAK: &str = ".flatpak";
 const EXT_BUNDLE_SNAP: &str = ".snap";
+const EXT_BUNDLE_APPIMAGE: &str = ".AppImage";
+const EXT_BUNDLE_MAC_PKG: &str = ".pkg";
+const EXT_BUNDLE_MSIX: &str = ".msix";
+const EXT_BUNDLE_APPX: &str = ".appx";
+const EXT_BUNDLE_NUPKG: &str = ".nupkg";
+const EXT_BUNDLE_APK: &str = ".apk";
+const EXT_BUNDLE_WHEEL: &str = ".whl";
+const EXT_BUNDLE_VSIX: &str = ".vsix";
+// .exe files are usually just the raw binary, so we only treat them as bundles
+// if the name also says it's some kind of setup program (as a whole word, so that
+// `cargo-binstall.exe` and `uninstall.exe` stay binaries)
+const EXT_BUNDLE_EXE: &str = ".exe";
+const SETUP_EXE_HINTS: &[&str] = &["setup", "install", "installer"];
 
-const KNOWN_WINDOWS_BUNDLE_EXTS: &[&str] = &[EXT_BUNDLE_MSI];
-const KNOWN_MAC_BUNDLE_EXTS: &[&str] = &[EXT_BUNDLE_APP, EXT_BUNDLE_DMG];
+const KNOWN_WINDOWS_BUNDLE_EXTS: &[&str] = &[
+    EXT_BUNDLE_MSI,
+    EXT_BUNDLE_MSIX,
+    EXT_BUNDLE_APPX,
+    EXT_BUNDLE_NUPKG,
+    EXT_BUNDLE_EXE,
+];
+const KNOWN_MAC_BUNDLE_EXTS: &[&str] = &[EXT_BUNDLE_APP, EXT_BUNDLE_DMG, EXT_BUNDLE_MAC_PKG];
 const KNOWN_LINUX_BUNDLE_EXTS: &[&str] = &[
     EXT_BUNDLE_DMG,
     EXT_BUNDLE_DEB,
@@ -63,7 +82,9 @@ const KNOWN_LINUX_BUNDLE_EXTS: &[&str] = &[
     EXT_BUNDLE_PACMAN,
     EXT_BUNDLE_FLATPAK,
     EXT_BUNDLE_SNAP,
+    EXT_BUNDLE_APPIMAGE,
 ];
+const KNOWN_ANDROID_BUNDLE_EXTS: &[&str] = &[EXT_BUNDLE_APK];
 const KNOWN_BUNDLE_EXTS: &[&str] = &[
     EXT_BUNDLE_MSI,
     EXT_BUNDLE_APP,
@@ -73,7 +94,43 @@ const KNOWN_BUNDLE_EXTS: &[&str] = &[
     EXT_BUNDLE_FLATPAK,
     EXT_BUNDLE_SNAP,
     EXT_BUNDLE_PACMAN,
+    EXT_BUNDLE_APPIMAGE,
+    EXT_BUNDLE_MAC_PKG,
+    EXT_BUNDLE_MSIX,
+    EXT_BUNDLE_APPX,
+    EXT_BUNDLE_NUPKG,
+    EXT_BUNDLE_APK,
+    EXT_BUNDLE_WHEEL,
+    EXT_BUNDLE_VSIX,
 ];
+// Bundles that are really "packages for some other ecosystem's package manager",
+// which are less of a sure thing than a native installer
+const KNOWN_PACKAGE_BUNDLE_EXTS: &[&str] = &[EXT_BUNDLE_NUPKG, EXT_BUNDLE_WHEEL, EXT_BUNDLE_VSIX];
+
+/// Android targets, which axoproject doesn't know about but .apk files are for
+pub(crate) const KNOWN_ANDROID_TARGETS: &[&str] = &[
+    "aarch64-linux-android",
+    "armv7-linux-androideabi",
+    "i686-linux-android",
+    "x86_64-linux-android",
+];
+
+// Other targets axoproject doesn't know about, which only package managers can claim
+// (see `system_targets`)
+pub(crate) const TARGET_PPC_MAC: &str = "powerpc-apple-darwin";
+pub(crate) const KNOWN_IOS_TARGETS: &[&str] = &["aarch64-apple-ios"];
+pub(crate) const KNOWN_FREEBSD_TARGETS: &[&str] = &["x86_64-unknown-freebsd"];
+pub(crate) const KNOWN_OTHER_TARGETS: &[&str] = &[
+    TARGET_PPC_MAC,
+    KNOWN_IOS_TARGETS[0],
+    KNOWN_FREEBSD_TARGETS[0],
+];
+
+// Target "vendor-os-abi" suffixes for non-Rust platform naming schemes (see `infer_targets_from_platform_tags`)
+const SUFFIX_WINDOWS: &str = "pc-windows-msvc";
+const SUFFIX_MAC: &str = "apple-darwin";
+const SUFFIX_LINUX_GNU: &str = "unknown-linux-gnu";
+const SUFFIX_LINUX_MUSL: &str = "unknown-linux-musl";
 
 // Various extensions for
 const EXT_SCRIPT_SHELL: &str = ".sh";
@@ -84,24 +141,44 @@ const KNOWN_WINDOWS_SCRIPT_EXTS: &[&str] = &[EXT_SCRIPT_POWERSHELL];
 const KNOWN_UNIX_SCRIPT_EXTS: &[&str] = &[EXT_SCRIPT_SHELL];
 pub(crate) const KNOWN_SCRIPT_EXTS: &[&str] = &[EXT_SCRIPT_SHELL, EXT_SCRIPT_POWERSHELL];
 
+// Files that are for the whole Release rather than one app in it
+const APP_AGNOSTIC_FILE_STEMS: &[&str] = &[
+    "install",
+    "installer",
+    "checksums",
+    "sha256sums",
+    "sha512sums",
+    "source",
+];
+
 impl ReleaseArtifacts {
     /// Infer installers/artifacts based solely on file names
     pub fn add_inference(&mut self) {
-        // Gotta clone this upfront to avoid borrowing stuff
-        let app_name = self.app_name.clone();
+        // If we're trying to restrict to specific apps, ignore files for other apps.
+        // If no file mentions any of them, assume the whole Release is for this app and
+        // don't filter anything.
+        let apps = self.app_names.clone();
+        let mentioned_app = |name: &str| apps.iter().find(|app| mentions_app(name, app)).cloned();
+        let filter_apps = self
+            .files()
+            .filter(|file| file.infer)
+            .any(|file| mentioned_app(&file.name).is_some());
         for file_idx in self.file_indices() {
-            let file = self.file_mut(file_idx);
+            let file = self.file(file_idx);
             // Skip this
             if !file.infer {
                 continue;
             }
-            if let Some(app_name) = &app_name {
-                // If we're trying to restrict to a specific app, ignore files that don't contain
-                // the app name (future-proofing for multi-tenant oranda work)
-                if !file.name.contains(app_name) {
-                    continue;
-                }
+            let app_name = mentioned_app(&file.name);
+            // Files like `install.sh` or `SHA256SUMS` aren't for any particular app, so keep those
+            if filter_apps && app_name.is_none() && !is_app_agnostic(&file.name) {
+                continue;
             }
+            // If we're showing multiple apps, label installers with the app they're for
+            let app_name = app_name.filter(|_| apps.len() > 1);
+            let file = self.file_mut(file_idx);
+            // Windows and macOS folks like their `Setup.EXE`s and `.DMG`s
+            let lower_name = file.name.to_ascii_lowercase();
 
             // Search for target triples in the file name
             let mut targets = vec![];
@@ -110,11 +187,16 @@ impl ReleaseArtifacts {
                     targets.push(target.to_owned());
                 }
             }
+            // Failing that, look for the platform tags other ecosystems use
+            if targets.is_empty() {
+                targets = infer_targets_from_platform_tags(&file.name);
+            }
 
             let label;
             let description = String::new();
             let method;
             let preference;
+            let mut systems = vec![];
 
             // Try to detect what kind of file this is
             if file.name.contains("install")
@@ -133,25 +215,25 @@ impl ReleaseArtifacts {
                     file: Some(file_idx),
                     run_hint,
                 };
-            } else if KNOWN_BUNDLE_EXTS.iter().any(|ext| file.name.ends_with(ext)) {
+            } else if KNOWN_BUNDLE_EXTS
+                .iter()
+                .any(|ext| has_bundle_ext(&lower_name, ext))
+                || is_setup_exe(&lower_name)
+            {
                 // Looks like an installer bundle! Recommend a download.
                 //
-                // NOTE: the above check is intentionally "contains" and not "ends_with" because
+                // NOTE: the above check is "contains" and not "ends_with" for pacman because
                 // arch packages are .pkg.tar.* and that's really annoying to handle.
                 //
                 // If this bundle doesn't have targets, infer them
                 if targets.is_empty() {
-                    targets = infer_targets_for_bundle(file);
+                    targets = infer_targets_for_bundle(&lower_name);
                 }
-                label = infer_label_for_bundle(file);
-                preference = InstallerPreference::Native;
+                label = infer_label_for_bundle(file, &lower_name);
+                preference = infer_preference_for_bundle(&lower_name);
+                systems = infer_systems_for_bundle(&lower_name);
                 method = InstallMethod::Download { file: file_idx };
-            } else if KNOWN_ARCHIVE_EXTS
-                .iter()
-                .copied()
-                .flatten()
-                .any(|ext| file.name.ends_with(ext))
-            {
+            } else if is_archive(&file.name) {
                 // Looks like this is an archive containing a binary! Recommend a download.
                 // Skip anything without a target triple, because we can't use it otherwise,
                 // and it might just be something like a source dump.
@@ -170,8 +252,9 @@ impl ReleaseArtifacts {
             let installer = Installer {
                 label,
                 description,
-                app_name: self.app_name.clone(),
+                app_name,
                 targets,
+                systems,
                 method,
                 display: DisplayPreference::Preferred,
             };
@@ -181,24 +264,24 @@ impl ReleaseArtifacts {
 }
 
 /// Given a file that appears to be a "bundle" but doesn't specify a target,
-/// infer the targets it applies to
-fn infer_targets_for_bundle(file: &File) -> Vec<TargetTriple> {
+/// infer the targets it applies to from its (lowercase) name
+fn infer_targets_for_bundle(name: &str) -> Vec<TargetTriple> {
     let mut targets = vec![];
     if KNOWN_WINDOWS_BUNDLE_EXTS
         .iter()
-        .any(|ext| file.name.contains(ext))
+        .any(|ext| has_bundle_ext(name, ext))
     {
         targets.extend(KNOWN_WINDOWS_TARGETS.iter().copied().map(|t| t.to_owned()));
     }
     if KNOWN_MAC_BUNDLE_EXTS
         .iter()
-        .any(|ext| file.name.contains(ext))
+        .any(|ext| has_bundle_ext(name, ext))
     {
         targets.extend(KNOWN_MAC_TARGETS.iter().copied().map(|t| t.to_owned()));
     }
     if KNOWN_LINUX_BUNDLE_EXTS
         .iter()
-        .any(|ext| file.name.contains(ext))
+        .any(|ext| has_bundle_ext(name, ext))
     {
         targets.extend(
             KNOWN_LINUX_TARGETS
@@ -209,9 +292,139 @@ fn infer_targets_for_bundle(file: &File) -> Vec<TargetTriple> {
                 .map(|t| t.to_owned()),
         );
     }
+    if KNOWN_ANDROID_BUNDLE_EXTS
+        .iter()
+        .any(|ext| has_bundle_ext(name, ext))
+    {
+        targets.extend(KNOWN_ANDROID_TARGETS.iter().copied().map(|t| t.to_owned()));
+    }
+    targets
+}
+
+/// Check if a file name looks like an archive (tarball, zip, ...)
+pub(crate) fn is_archive(name: &str) -> bool {
+    KNOWN_ARCHIVE_EXTS
+        .iter()
+        .copied()
+        .flatten()
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Whether the (lowercase) file name has the given bundle extension
+///
+/// This is "ends_with" for everything but pacman's .pkg.tar.*, which needs "contains"
+fn has_bundle_ext(name: &str, ext: &str) -> bool {
+    let ext_lower = ext.to_ascii_lowercase();
+    if ext == EXT_BUNDLE_PACMAN {
+        name.contains(&ext_lower)
+    } else {
+        name.ends_with(&ext_lower)
+    }
+}
+
+/// Whether this (lowercase) file name looks like a Windows setup program (as opposed to a bare binary)
+fn is_setup_exe(name: &str) -> bool {
+    name.ends_with(EXT_BUNDLE_EXE) && SETUP_EXE_HINTS.iter().any(|hint| mentions_app(name, hint))
+}
+
+/// Whether a file name mentions this app, either as a prefix (`axolotlsay-x86_64.tar.gz`)
+/// or as a `-`/`_`/`.`-separated part of it (`x86_64_axolotlsay.zip`, `axolotlsay.exe`)
+///
+/// Unlike a plain `contains`, this doesn't think `axolotlsay.zip` is for an app called `axo`.
+fn mentions_app(name: &str, app: &str) -> bool {
+    if app.is_empty() {
+        return false;
+    }
+    let is_separator = |c: char| matches!(c, '-' | '_' | '.');
+    name.match_indices(app).any(|(start, _)| {
+        let before = name[..start].chars().next_back();
+        let after = name[start + app.len()..].chars().next();
+        before.map_or(true, is_separator) && after.map_or(true, is_separator)
+    })
+}
+
+/// Whether this file is for every app in the Release, like `install.sh` or `SHA256SUMS`
+fn is_app_agnostic(name: &str) -> bool {
+    let stem = name
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    APP_AGNOSTIC_FILE_STEMS.contains(&stem.as_str())
+}
+
+/// Find target triples in a file name that uses non-Rust platform naming
+///
+/// This understands things like Python wheel tags (`manylinux_2_17_x86_64`,
+/// `macosx_11_0_arm64`, `win_amd64`) and Go's `GOOS_GOARCH` (`linux_amd64`, `darwin-arm64`).
+fn infer_targets_from_platform_tags(name: &str) -> Vec<TargetTriple> {
+    let name = name.to_ascii_lowercase();
+    let tokens: Vec<&str> = name.split(['-', '_', '.']).collect();
+    let mut targets = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        let suffix = match *token {
+            "windows" | "win" => SUFFIX_WINDOWS,
+            // win32 is both the os and the arch
+            "win32" => {
+                targets.push(format!("i686-{SUFFIX_WINDOWS}"));
+                continue;
+            }
+            "darwin" | "macos" | "macosx" | "osx" => SUFFIX_MAC,
+            "linux" | "manylinux" | "manylinux1" | "manylinux2010" | "manylinux2014" => {
+                SUFFIX_LINUX_GNU
+            }
+            "musllinux" => SUFFIX_LINUX_MUSL,
+            _ => continue,
+        };
+
+        // Skip over any version numbers (`macosx_11_0_arm64`) to find the arch
+        let mut rest = tokens[idx + 1..].iter().copied().peekable();
+        while let Some(token) = rest.next() {
+            let arches: &[&str] = match token {
+                "amd64" | "x64" | "x86_64" => &["x86_64"],
+                // "x86_64" got split in two by our tokenizer
+                "x86" if rest.peek() == Some(&"64") => &["x86_64"],
+                "386" | "i386" | "i686" | "x86" => &["i686"],
+                "arm64" | "aarch64" => &["aarch64"],
+                "universal2" | "universal" => &["x86_64", "aarch64"],
+                _ if token.chars().all(|c| c.is_ascii_digit()) => continue,
+                _ => break,
+            };
+            for arch in arches {
+                let target = format!("{arch}-{suffix}");
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+            break;
+        }
+    }
     targets
 }
 
+/// Infer which Linux distros a bundle is native to, from its (lowercase) name
+fn infer_systems_for_bundle(name: &str) -> Vec<ArtifactSystem> {
+    if name.ends_with(EXT_BUNDLE_DEB) {
+        ArtifactSystem::DEB_DISTROS.to_vec()
+    } else if name.ends_with(EXT_BUNDLE_RPM) {
+        ArtifactSystem::RPM_DISTROS.to_vec()
+    } else {
+        vec![]
+    }
+}
+
+/// Infer how much we like a bundle, from its (lowercase) name
+fn infer_preference_for_bundle(name: &str) -> InstallerPreference {
+    if KNOWN_PACKAGE_BUNDLE_EXTS
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    {
+        InstallerPreference::Custom
+    } else {
+        InstallerPreference::Native
+    }
+}
+
 /// Given a file that appears to be a "script" but doesn't specify a target, infer the targets it applies to
 fn infer_targets_for_script(file: &File) -> Vec<TargetTriple> {
     let mut targets = vec![];
@@ -254,8 +467,14 @@ fn infer_run_hint_for_script(file: &File) -> String {
     }
 }
 
-/// Infer the label for a bundle
-fn infer_label_for_bundle(file: &File) -> String {
+/// Infer the label for a bundle (`lower_name` being its name in lowercase)
+fn infer_label_for_bundle(file: &File, lower_name: &str) -> String {
+    if lower_name.ends_with(EXT_BUNDLE_WHEEL) {
+        return "wheel".to_owned();
+    }
+    if lower_name.contains(EXT_BUNDLE_PACMAN) {
+        return "pacman".to_owned();
+    }
     // For now just use the extension
     Utf8PathBuf::from(&file.name)
         .extension()
@@ -7,6 +7,7 @@
 //! * [`ReleaseArtifacts::add_github`][] (in different file)
 //! * [`ReleaseArtifacts::add_cargo_dist`][] (in a different file)
 //! * [`ReleaseArtifacts::add_inference`][] (in a different file)
+//! * [`ReleaseArtifacts::add_signatures`][] (in a different file)
 //! * [`ReleaseArtifacts::add_package_managers`][]
 //!
 //! One you've added all the data you want, call [`ReleaseArtifacts::select_installers`][]
@@ -14,19 +15,28 @@
 
 use std::collections::{BTreeMap, HashMap};
 
-use axoasset::{LocalAsset, RemoteAsset};
-use axoproject::platforms::KNOWN_TARGET_TRIPLES;
+use axoasset::LocalAsset;
+use axoproject::platforms::{
+    KNOWN_LINUX_TARGETS, KNOWN_MAC_TARGETS, KNOWN_TARGET_TRIPLES, KNOWN_WINDOWS_TARGETS,
+    TARGET_ARM64_MAC, TARGET_ARM64_WINDOWS, TARGET_X64_WINDOWS, TARGET_X86_MAC,
+};
 use camino::Utf8PathBuf;
 use indexmap::IndexMap;
 use serde::{Serialize, Serializer};
 
 use crate::config::ArtifactsConfig;
-use crate::config::Config;
+pub use crate::config::InstallerPreference;
+use crate::config::{ArtifactSystem, Config, PackageManager};
 use crate::errors::*;
+use crate::http_cache;
 
-use inference::KNOWN_SCRIPT_EXTS;
+use inference::{
+    KNOWN_ANDROID_TARGETS, KNOWN_FREEBSD_TARGETS, KNOWN_IOS_TARGETS, KNOWN_OTHER_TARGETS,
+    KNOWN_SCRIPT_EXTS, TARGET_PPC_MAC,
+};
 
 pub mod inference;
+pub mod signatures;
 
 /// A Target Triple like x86_64-pc-windows-msvc
 pub type TargetTriple = String;
@@ -40,10 +50,13 @@ pub type FileName = String;
 /// Info about the artifacts of a Release
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct ReleaseArtifacts {
-    /// An app to focus on. This enables multi-tenant oranda to filter out
+    /// Apps to focus on. This enables multi-tenant oranda to filter out
     /// files for other apps in the same Github Release / directory.
+    ///
+    /// If empty, or if nothing in the Release mentions any of these apps,
+    /// everything is shown.
     #[serde(skip)]
-    pub(crate) app_name: Option<String>,
+    pub(crate) app_names: Vec<AppName>,
     /// Files found in the Release
     #[serde(serialize_with = "flatten_files")]
     files: IndexMap<FileName, File>,
@@ -52,6 +65,10 @@ pub struct ReleaseArtifacts {
     /// What installers to use for each target, in descending order
     /// (so recommend the first one, potentially show the others in tabs)
     targets: BTreeMap<TargetTriple, Vec<InstallerIdx>>,
+    /// Archives of the source code, for building it yourself
+    source_tarballs: Vec<FileIdx>,
+    /// Files that are only interesting to developers (e.g. debug symbols)
+    developer_files: Vec<FileIdx>,
 }
 
 /// A handle to a File (equivalent to a pointer into `ReleaseArtifacts::files`)
@@ -69,6 +86,11 @@ pub struct File {
     pub view_path: Option<String>,
     /// A file containing checksums for this one
     pub checksum_file: Option<FileIdx>,
+    /// A detached signature for this one
+    pub signature: Option<Signature>,
+    /// Where the file is on disk, if it hasn't been published yet
+    #[serde(skip)]
+    pub local_path: Option<Utf8PathBuf>,
     /// Whether artifact_inference should process this file
     ///
     /// Starts true, but can be set to false by other steps to avoid suggesting an installer twice
@@ -76,6 +98,27 @@ pub struct File {
     pub infer: bool,
 }
 
+/// A detached signature (or signature bundle) for a File
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct Signature {
+    /// The file containing the signature
+    pub file: FileIdx,
+    /// How the file was signed
+    pub scheme: SignatureScheme,
+}
+
+/// Ways a file can be signed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// A PGP signature (.asc/.sig)
+    Gpg,
+    /// A minisign signature (.minisig)
+    Minisign,
+    /// A sigstore/cosign bundle
+    Cosign,
+}
+
 /// A handle to an Installer (equivalent to a pointer into [`ReleaseArtifacts::installers`][])
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize)]
 pub struct InstallerIdx(pub usize);
@@ -93,6 +136,10 @@ pub struct Installer {
     /// What targets this supports, and how this installer should be preferred on that target
     #[serde(skip)]
     pub targets: HashMap<TargetTriple, InstallerPreference>,
+    /// Specific systems (Linux distros) this installer is native to
+    ///
+    /// Empty if it's equally good on every system its targets cover
+    pub systems: Vec<ArtifactSystem>,
     /// The way we should suggest this installer
     pub method: InstallMethod,
     /// Whether this installer should be ignored by select_installers
@@ -101,21 +148,6 @@ pub struct Installer {
     pub display: DisplayPreference,
 }
 
-/// How much an installer should be preferred (descending order)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
-pub enum InstallerPreference {
-    /// The best way
-    Preferred,
-    /// Some kind of "native" installer like a .msi or .dmg
-    Native,
-    /// Some kind of curl|sh script
-    Script,
-    /// Any kind of custom/misc/unknown solution
-    Custom,
-    /// Just a tarball containing the binary
-    Archive,
-}
-
 /// Where to show the installer
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub enum DisplayPreference {
@@ -148,10 +180,10 @@ pub enum InstallMethod {
 impl ReleaseArtifacts {
     /// Create a new set of artifacts for a Release
     ///
-    /// Optionally filtered down to the given app (for multi-tenant stuff)
-    pub fn new(app_name: Option<AppName>) -> Self {
+    /// Optionally filtered down to the given apps (for multi-tenant stuff)
+    pub fn new(app_names: Vec<AppName>) -> Self {
         Self {
-            app_name,
+            app_names,
             ..Self::default()
         }
     }
@@ -201,6 +233,30 @@ impl ReleaseArtifacts {
     pub fn files(&self) -> impl Iterator<Item = &File> {
         self.files.values()
     }
+    /// Mark a file as an archive of the source code
+    pub fn add_source_tarball(&mut self, idx: FileIdx) {
+        if !self.source_tarballs.contains(&idx) {
+            self.source_tarballs.push(idx);
+        }
+    }
+    /// Get all the source code archives
+    pub fn source_tarballs(&self) -> impl Iterator<Item = (FileIdx, &File)> {
+        self.source_tarballs
+            .iter()
+            .map(|&idx| (idx, self.file(idx)))
+    }
+    /// Mark a file as only interesting to developers
+    pub fn add_developer_file(&mut self, idx: FileIdx) {
+        if !self.developer_files.contains(&idx) {
+            self.developer_files.push(idx);
+        }
+    }
+    /// Get all the files that are only interesting to developers
+    pub fn developer_files(&self) -> impl Iterator<Item = (FileIdx, &File)> {
+        self.developer_files
+            .iter()
+            .map(|&idx| (idx, self.file(idx)))
+    }
     /// Get an installer
     pub fn installer(&self, idx: InstallerIdx) -> &Installer {
         &self.installers[idx.0]
@@ -217,28 +273,57 @@ impl ReleaseArtifacts {
         &self.targets
     }
 
-    /// Add custom package manager values from the config
+    /// Add package manager values from the config
+    ///
+    /// This includes both the ones the user specified and the ones we detected from
+    /// their project manifest, with the user's taking priority.
     pub fn add_package_managers(&mut self, config: &ArtifactsConfig) {
-        // If we have a custom item for "npm" or "npx", then supress any entries
-        // from earlier layers like cargo-dist that were also trying to specify this
-        if config.package_managers.has_npm() {
-            if let Some(installer) = self
-                .installers
-                .iter_mut()
-                .find(|installer| installer.label == "npm")
-            {
+        let custom = &config.package_managers;
+        // If we have a custom item with the same name as an entry from earlier layers
+        // like cargo-dist, then supress that entry. "npm" and "npx" are interchangeable.
+        for installer in &mut self.installers {
+            if custom.has(&installer.label) || (installer.label == "npm" && custom.has_npm()) {
                 installer.display = DisplayPreference::Hidden;
             }
         }
-        for (label, script) in &config.package_managers.preferred {
-            let mut installer = simple_run_installer(label, script);
-            installer.display = DisplayPreference::Preferred;
-            self.add_installer(installer);
-        }
 
-        for (label, script) in &config.package_managers.additional {
-            let mut installer = simple_run_installer(label, script);
-            installer.display = DisplayPreference::Additional;
+        // Detected package managers lose to anything else that has the same name
+        let detected = &config.detected_package_managers;
+        let existing: Vec<String> = self.installers.iter().map(|i| i.label.clone()).collect();
+        let is_overridden = |label: &String| {
+            custom.has(label) || existing.contains(label) || (label == "npx" && custom.has_npm())
+        };
+        let detected_preferred = detected
+            .preferred
+            .iter()
+            .filter(|(label, _)| !is_overridden(label))
+            .map(|entry| (entry, DisplayPreference::Preferred));
+        let detected_additional = detected
+            .additional
+            .iter()
+            .filter(|(label, _)| !is_overridden(label))
+            .map(|entry| (entry, DisplayPreference::Additional));
+
+        let custom_preferred = custom
+            .preferred
+            .iter()
+            .map(|entry| (entry, DisplayPreference::Preferred));
+        let custom_additional = custom
+            .additional
+            .iter()
+            .map(|entry| (entry, DisplayPreference::Additional));
+
+        let all = detected_preferred
+            .chain(detected_additional)
+            .chain(custom_preferred)
+            .chain(custom_additional)
+            .map(|((label, package_manager), display)| {
+                let mut installer = package_manager_installer(label, package_manager);
+                installer.display = display;
+                installer
+            })
+            .collect::<Vec<_>>();
+        for installer in all {
             self.add_installer(installer);
         }
     }
@@ -251,7 +336,7 @@ impl ReleaseArtifacts {
                 installer.display = DisplayPreference::Hidden;
             }
         }
-        for target in KNOWN_TARGET_TRIPLES.iter().copied().flatten().copied() {
+        for target in all_targets() {
             // Gather up all the installers into an array
             let mut installers = vec![];
             for (idx, installer) in self.installers() {
@@ -330,20 +415,26 @@ fn write_source(config: &Config, file: &File) -> Result<String> {
     let file_path = format!("{}.txt", &file.name);
     let full_file_path = Utf8PathBuf::from(&config.build.dist_dir).join(&file_path);
     if !full_file_path.exists() {
-        let file_string_future = RemoteAsset::load_string(&file.download_url);
-        let file_string = tokio::runtime::Handle::current().block_on(file_string_future)?;
+        let file_string = if let Some(local_path) = &file.local_path {
+            LocalAsset::load_string(local_path)?
+        } else {
+            let file_string_future = http_cache::load_string(&file.download_url);
+            tokio::runtime::Handle::current().block_on(file_string_future)?
+        };
         LocalAsset::write_new(&file_string, &full_file_path)?;
     }
     Ok(file_path)
 }
 
-fn simple_run_installer(label: &str, script: &str) -> Installer {
-    let run_hint = script.to_owned();
+fn package_manager_installer(label: &str, package_manager: &PackageManager) -> Installer {
+    let run_hint = package_manager.command.clone();
+    let (targets, systems) = package_manager_targets(label, &package_manager.targets);
     Installer {
         label: label.to_owned(),
-        description: String::new(),
+        description: package_manager.description.clone().unwrap_or_default(),
         app_name: None,
-        targets: preference_to_targets(vec![], InstallerPreference::Custom),
+        targets: preference_to_targets(targets, package_manager.preference),
+        systems,
         method: InstallMethod::Run {
             file: None,
             run_hint,
@@ -351,3 +442,81 @@ fn simple_run_installer(label: &str, script: &str) -> Installer {
         display: DisplayPreference::Preferred,
     }
 }
+
+/// Resolve the targets a package manager says it supports into actual target triples
+///
+/// These can be triples, OS families, or any [`ArtifactSystem`][] (Linux distros also get
+/// returned, so the install widget can prefer the package manager on that distro).
+fn package_manager_targets(
+    label: &str,
+    entries: &[String],
+) -> (Vec<TargetTriple>, Vec<ArtifactSystem>) {
+    let mut targets = vec![];
+    let mut systems = vec![];
+    for entry in entries {
+        let family: Vec<&str> = match entry.as_str() {
+            "macos" => system_targets(ArtifactSystem::Mac),
+            "unix" => {
+                let mut unix = system_targets(ArtifactSystem::Linux);
+                unix.extend(system_targets(ArtifactSystem::Mac));
+                unix
+            }
+            other => {
+                if let Some(system) = ArtifactSystem::from_id(other) {
+                    if system.is_linux_distro() {
+                        systems.push(system);
+                    }
+                    system_targets(system)
+                } else {
+                    if !all_targets().any(|t| t == other) {
+                        tracing::warn!(
+                            "package manager {label} has an unknown target \"{other}\", it will never be shown"
+                        );
+                    }
+                    vec![other]
+                }
+            }
+        };
+        for target in family {
+            if !targets.iter().any(|t| t == target) {
+                targets.push(target.to_owned());
+            }
+        }
+    }
+    (targets, systems)
+}
+
+/// Every target the install widget can show
+fn all_targets() -> impl Iterator<Item = &'static str> {
+    KNOWN_TARGET_TRIPLES
+        .iter()
+        .chain(&[KNOWN_ANDROID_TARGETS, KNOWN_OTHER_TARGETS])
+        .copied()
+        .flatten()
+        .copied()
+}
+
+/// The target triples a system covers
+fn system_targets(system: ArtifactSystem) -> Vec<&'static str> {
+    let linux_targets = || KNOWN_LINUX_TARGETS.iter().copied().flatten().copied();
+    match system {
+        ArtifactSystem::Windows => KNOWN_WINDOWS_TARGETS.to_vec(),
+        ArtifactSystem::Windows64 => vec![TARGET_X64_WINDOWS],
+        ArtifactSystem::WindowsArm => vec![TARGET_ARM64_WINDOWS],
+        ArtifactSystem::Mac => KNOWN_MAC_TARGETS.to_vec(),
+        ArtifactSystem::MacPpc => vec![TARGET_PPC_MAC],
+        ArtifactSystem::Mac32 => vec![TARGET_X86_MAC],
+        ArtifactSystem::MacSilicon => vec![TARGET_ARM64_MAC],
+        ArtifactSystem::Linux
+        | ArtifactSystem::LinuxUbuntu
+        | ArtifactSystem::LinuxDebian
+        | ArtifactSystem::LinuxMandriva
+        | ArtifactSystem::LinuxRedhat
+        | ArtifactSystem::LinuxFedora
+        | ArtifactSystem::LinuxSuse
+        | ArtifactSystem::LinuxGentoo => linux_targets().collect(),
+        ArtifactSystem::Ios => KNOWN_IOS_TARGETS.to_vec(),
+        ArtifactSystem::Android => KNOWN_ANDROID_TARGETS.to_vec(),
+        ArtifactSystem::Freebsd => KNOWN_FREEBSD_TARGETS.to_vec(),
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+// Extensions for detached signatures, and the scheme they're for.
+//
+// These are checked in order, so longer extensions need to come before
+// any extension they end with.
+const KNOWN_SIGNATURE_EXTS: &[(&str, SignatureScheme)] = &[
+    (".sigstore.json", SignatureScheme::Cosign),
+    (".cosign.bundle", SignatureScheme::Cosign),
+    (".sigstore", SignatureScheme::Cosign),
+    (".minisig", SignatureScheme::Minisign),
+    (".asc", SignatureScheme::Gpg),
+    (".sig", SignatureScheme::Gpg),
+];
+
+impl ReleaseArtifacts {
+    /// Pair up signature files with the files they sign, based on their names
+    ///
+    /// So `my-app.tar.gz.minisig` becomes the signature of `my-app.tar.gz`.
+    /// Signature files are never inferred to be installers themselves.
+    pub fn add_signatures(&mut self) {
+        for sig_idx in self.file_indices() {
+            let sig_name = &self.file(sig_idx).name;
+            let Some((signed_name, scheme)) = KNOWN_SIGNATURE_EXTS
+                .iter()
+                .find_map(|(ext, scheme)| Some((sig_name.strip_suffix(ext)?, *scheme)))
+            else {
+                continue;
+            };
+            let Some(signed_idx) = self.file_idx(&signed_name.to_owned()) else {
+                continue;
+            };
+            self.file_mut(signed_idx).signature = Some(Signature {
+                file: sig_idx,
+                scheme,
+            });
+            self.file_mut(sig_idx).infer = false;
+        }
+    }
+}
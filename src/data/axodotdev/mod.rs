@@ -1,4 +1,5 @@
 use crate::errors::*;
+use crate::http_cache::HttpCache;
 
 use axoproject::GithubRepo;
 use gazenot::{Gazenot, PublicRelease, ReleaseAsset};
@@ -32,6 +33,12 @@ pub struct AxoReleaseAsset {
 
 impl AxoRelease {
     pub async fn fetch_all(package_name: &str, repo: &GithubRepo) -> Result<Vec<AxoRelease>> {
+        // axo Releases are fetched with their own client, which we can't cache
+        if HttpCache::is_offline() {
+            return Err(OrandaError::OfflineCacheMiss {
+                url: format!("axo Releases for {package_name}"),
+            });
+        }
         let abyss = Gazenot::new_unauthed("github".to_string(), repo.owner.clone())?;
         let list = abyss
             .list_releases_many(vec![package_name.to_string()])
@@ -84,6 +91,8 @@ impl ReleaseArtifacts {
                 download_url: asset.browser_download_url.clone(),
                 view_path: None,
                 checksum_file: None,
+                signature: None,
+                local_path: None,
                 infer: true,
             };
             self.add_file(file);
@@ -1,6 +1,7 @@
 use camino::Utf8PathBuf;
 pub use cargo_dist_schema::{ArtifactKind, DistManifest};
 
+use super::artifacts::inference::is_archive;
 use super::artifacts::{
     preference_to_targets, DisplayPreference, InstallMethod, Installer, InstallerPreference,
     ReleaseArtifacts,
@@ -15,13 +16,28 @@ impl ReleaseArtifacts {
         // by e.g. calling `add_github` or whatever future system for discovering artifacts.
         // If the manifest refers to files that don't exist, they will be skipped.
 
+        // If we're trying to restrict to specific apps, ignore releases of other ones.
+        // If the manifest doesn't mention any of them, assume it's all for this app.
+        let filter_apps = manifest
+            .releases
+            .iter()
+            .any(|app| self.app_names.contains(&app.app_name));
+        let shown_apps = manifest
+            .releases
+            .iter()
+            .filter(|app| !filter_apps || self.app_names.contains(&app.app_name))
+            .count();
+
         for app in &manifest.releases {
-            // If we're trying to restrict to a specific app, ignore releases of other ones
-            // (future-proofing for multi-tenant oranda work)
-            if let Some(app_name) = &self.app_name {
-                if app_name != &app.app_name {
-                    continue;
+            if filter_apps && !self.app_names.contains(&app.app_name) {
+                // Make sure inference doesn't pick up this app's files either
+                for (_, artifact) in manifest.artifacts_for_release(app) {
+                    let file = artifact.name.as_ref().and_then(|n| self.file_idx(n));
+                    if let Some(file) = file {
+                        self.file_mut(file).infer = false;
+                    }
                 }
+                continue;
             }
 
             for (id, artifact) in manifest.artifacts_for_release(app) {
@@ -94,9 +110,41 @@ impl ReleaseArtifacts {
                             Utf8PathBuf::from(id).extension().unwrap_or(id).to_owned()
                         };
                     }
+                    ArtifactKind::Symbols => {
+                        // Debug symbols are only interesting to developers, list them separately
+                        if let Some(file) = file {
+                            self.add_developer_file(file);
+                            self.file_mut(file).infer = false;
+                        }
+                        continue;
+                    }
+                    ArtifactKind::Checksum => {
+                        // Usually the artifact this is for already points to it,
+                        // but if it doesn't, it's named after the file it's for
+                        if let Some(file) = file {
+                            let checksummed = Utf8PathBuf::from(id)
+                                .file_stem()
+                                .and_then(|name| self.file_idx(&name.to_owned()));
+                            if let Some(checksummed) = checksummed {
+                                let checksummed = self.file_mut(checksummed);
+                                checksummed.checksum_file.get_or_insert(file);
+                            }
+                            self.file_mut(file).infer = false;
+                        }
+                        continue;
+                    }
                     _ => {
-                        // We don't care about these *yet*
-                        // (notably skipped: Symbols, Checksum)
+                        // Newer versions of cargo-dist have kinds this version of the schema
+                        // doesn't know about. Archives that aren't for any target are source
+                        // tarballs, anything else we don't care about *yet*.
+                        if let Some(file) = file {
+                            if artifact.target_triples.is_empty()
+                                && is_archive(&self.file(file).name)
+                            {
+                                self.add_source_tarball(file);
+                                self.file_mut(file).infer = false;
+                            }
+                        }
                         continue;
                     }
                 };
@@ -104,8 +152,9 @@ impl ReleaseArtifacts {
                 let installer = Installer {
                     label,
                     description: artifact.description.clone().unwrap_or_default(),
-                    app_name: (manifest.releases.len() > 1).then_some(app.app_name.clone()),
+                    app_name: (shown_apps > 1).then_some(app.app_name.clone()),
                     targets,
+                    systems: vec![],
                     method,
                     display: DisplayPreference::Preferred,
                 };
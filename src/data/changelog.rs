@@ -0,0 +1,266 @@
+//! Changelog files (like `CHANGELOG.md`), read on their own without any release hosting
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::errors::*;
+
+/// The names we look for a changelog file under, in order of preference
+pub const CHANGELOG_FILENAMES: &[&str] = &[
+    "CHANGELOG.md",
+    "CHANGELOG",
+    "CHANGES.md",
+    "CHANGES",
+    "HISTORY.md",
+    "HISTORY",
+];
+
+/// An entry made from a version heading in a changelog file
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangelogEntry {
+    /// The version in the heading, without any prefix like "v"
+    pub version: String,
+    /// The whole heading, with any links removed
+    pub title: String,
+    /// The date in the heading (as YYYY-MM-DD), if it has one
+    pub date: Option<String>,
+    /// Everything under the heading
+    pub notes: String,
+    pub prerelease: bool,
+}
+
+impl ChangelogEntry {
+    /// Find the changelog file to read
+    ///
+    /// An explicitly configured `path` is always used (relative to `dir`), otherwise
+    /// we look for one of the [`CHANGELOG_FILENAMES`][] in `dir`.
+    pub fn find_file(dir: &Utf8Path, path: Option<&str>) -> Option<Utf8PathBuf> {
+        if let Some(path) = path {
+            return Some(dir.join(path));
+        }
+        CHANGELOG_FILENAMES
+            .iter()
+            .map(|filename| dir.join(filename))
+            .find(|path| path.is_file())
+    }
+
+    /// Read every version heading in a changelog file as an entry, from top to bottom
+    ///
+    /// The "Unreleased" section of a [Keep a Changelog][] file isn't a release, so it's skipped.
+    ///
+    /// [Keep a Changelog]: https://keepachangelog.com
+    pub fn load_all(path: &Utf8Path) -> Result<Vec<ChangelogEntry>> {
+        let contents = LocalAsset::load_string(path)?;
+        let changelog = parse_changelog::parse(&contents).map_err(|details| {
+            OrandaError::ChangelogFileParseFailed {
+                path: path.to_owned(),
+                details,
+            }
+        })?;
+        Ok(changelog
+            .values()
+            .filter(|release| release.version != "Unreleased")
+            .map(|release| {
+                let title = release.title_no_link().into_owned();
+                ChangelogEntry {
+                    version: release.version.to_owned(),
+                    date: date_from_heading(&title),
+                    notes: release.notes.to_owned(),
+                    prerelease: release.version.contains('-'),
+                    title,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Find the release date in a changelog heading
+///
+/// [Keep a Changelog][] puts an ISO 8601 date after the version (`## [1.0.0] - 2017-06-20`),
+/// but we also accept it anywhere else in the heading (`## 1.0.0 (2017-06-20)`).
+///
+/// [Keep a Changelog]: https://keepachangelog.com
+pub fn date_from_heading(heading: &str) -> Option<String> {
+    heading
+        .char_indices()
+        .filter_map(|(idx, _)| heading.get(idx..idx + "YYYY-MM-DD".len()))
+        .filter(|candidate| {
+            // chrono is lenient about whitespace and digit counts, we aren't
+            candidate.bytes().enumerate().all(|(idx, byte)| match idx {
+                4 | 7 => byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+        })
+        .find_map(|candidate| NaiveDate::parse_from_str(candidate, "%Y-%m-%d").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// A kind of change, as [Keep a Changelog][] groups them
+///
+/// [Keep a Changelog]: https://keepachangelog.com
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangelogCategory {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+    /// A section under some other heading
+    Other,
+}
+
+impl ChangelogCategory {
+    const KNOWN: [ChangelogCategory; 6] = [
+        ChangelogCategory::Added,
+        ChangelogCategory::Changed,
+        ChangelogCategory::Deprecated,
+        ChangelogCategory::Removed,
+        ChangelogCategory::Fixed,
+        ChangelogCategory::Security,
+    ];
+
+    /// The category a section heading is for (ignoring case)
+    pub fn from_heading(heading: &str) -> ChangelogCategory {
+        Self::KNOWN
+            .into_iter()
+            .find(|category| category.heading().eq_ignore_ascii_case(heading))
+            .unwrap_or(ChangelogCategory::Other)
+    }
+
+    /// The usual heading of a section of this category
+    pub fn heading(&self) -> &'static str {
+        match self {
+            ChangelogCategory::Added => "Added",
+            ChangelogCategory::Changed => "Changed",
+            ChangelogCategory::Deprecated => "Deprecated",
+            ChangelogCategory::Removed => "Removed",
+            ChangelogCategory::Fixed => "Fixed",
+            ChangelogCategory::Security => "Security",
+            ChangelogCategory::Other => "Other",
+        }
+    }
+}
+
+/// A section of the notes for a release, like everything under `### Fixed`
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangelogSection {
+    pub category: ChangelogCategory,
+    /// The heading, as written
+    pub title: String,
+    /// Everything under the heading (as markdown)
+    pub notes: String,
+    /// How many list items are in the section (not counting nested ones)
+    pub entries: usize,
+}
+
+/// The notes for a release, split up into [`ChangelogSection`][]s
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ChangelogSections {
+    /// Anything before the first section (as markdown)
+    pub intro: String,
+    pub sections: Vec<ChangelogSection>,
+}
+
+impl ChangelogSections {
+    /// Split release notes into sections, if they follow [Keep a Changelog][]
+    ///
+    /// The sections start at the first heading for a known category (like `### Added`), and
+    /// any other heading at the same level starts an [`ChangelogCategory::Other`][] section.
+    /// Notes without any known category headings aren't split up, so this returns `None`.
+    ///
+    /// [Keep a Changelog]: https://keepachangelog.com
+    pub fn parse(notes: &str) -> Option<ChangelogSections> {
+        let mut result = ChangelogSections::default();
+        let mut level = None;
+        let mut in_code_block = false;
+        for line in notes.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+            }
+            let heading = (!in_code_block).then(|| heading(line)).flatten();
+            match (heading, level) {
+                (Some((heading_level, title)), None)
+                    if ChangelogCategory::from_heading(title) != ChangelogCategory::Other =>
+                {
+                    level = Some(heading_level);
+                    result.push_section(title);
+                }
+                (Some((heading_level, title)), Some(level)) if heading_level <= level => {
+                    result.push_section(title);
+                }
+                _ => {
+                    let notes = match result.sections.last_mut() {
+                        Some(section) => {
+                            if !in_code_block && is_list_item(line) {
+                                section.entries += 1;
+                            }
+                            &mut section.notes
+                        }
+                        None => &mut result.intro,
+                    };
+                    notes.push_str(line);
+                    notes.push('\n');
+                }
+            }
+        }
+        level?;
+        // Templates for new releases often have headings with nothing under them
+        result
+            .sections
+            .retain(|section| !section.notes.trim().is_empty());
+        Some(result)
+    }
+
+    /// Whether any section is about security fixes
+    pub fn has_security(&self) -> bool {
+        self.sections
+            .iter()
+            .any(|section| section.category == ChangelogCategory::Security)
+    }
+
+    fn push_section(&mut self, title: &str) {
+        self.sections.push(ChangelogSection {
+            category: ChangelogCategory::from_heading(title),
+            title: title.to_owned(),
+            notes: String::new(),
+            entries: 0,
+        });
+    }
+}
+
+/// The level and text of an ATX heading (`### Added`)
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.bytes().take_while(|byte| *byte == b' ').count();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let level = line.bytes().take_while(|byte| *byte == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// Whether a line starts a top-level list item
+fn is_list_item(line: &str) -> bool {
+    if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        return rest.starts_with([' ', '\t']);
+    }
+    let digits = line
+        .bytes()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    digits > 0
+        && line[digits..].starts_with(['.', ')'])
+        && line[digits + 1..].starts_with([' ', '\t'])
+}
@@ -0,0 +1,157 @@
+use crate::config::ForgeConfig;
+use crate::errors::*;
+use crate::http_cache;
+
+use serde::{Deserialize, Serialize};
+
+use super::artifacts::{File, ReleaseArtifacts};
+use super::{fetch_all_pages, split_repo_url};
+
+/// How many releases to ask for per page (the default maximum for Forgejo and Gitea,
+/// though instances can be configured to send fewer)
+const PER_PAGE: usize = 50;
+/// The token variable we read if the user doesn't pick one
+const DEFAULT_TOKEN_ENV: &str = "FORGEJO_TOKEN";
+
+/// A repository on a Forgejo or Gitea instance (like Codeberg)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForgejoRepo {
+    /// The instance's URL, like "https://codeberg.org"
+    pub base_url: String,
+    pub owner: String,
+    pub name: String,
+}
+
+/// From the Forgejo/Gitea REST API
+/// as documented here: <https://codeberg.org/api/swagger#/repository/repoListReleases>
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForgejoRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub created_at: String,
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<ForgejoReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForgejoReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl ForgejoRepo {
+    /// Get the repository from a repository URL (https or ssh)
+    ///
+    /// The instance URL defaults to the repository's host, but can be overridden.
+    pub fn from_url(repo_url: &str, config: &ForgeConfig) -> Result<Self> {
+        let (host, path) = split_repo_url(repo_url)?;
+        let mut segments = path.split('/');
+        let (Some(owner), Some(name)) = (segments.next(), segments.next()) else {
+            return Err(OrandaError::UnknownRepoStyle {
+                url: repo_url.to_owned(),
+            });
+        };
+        let base_url = config.base_url.clone().unwrap_or(host);
+        Ok(ForgejoRepo {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+        })
+    }
+
+    /// The URL of the repository's releases API
+    fn releases_url(&self) -> String {
+        format!(
+            "{}/api/v1/repos/{}/{}/releases",
+            self.base_url, self.owner, self.name
+        )
+    }
+}
+
+impl ForgejoRelease {
+    pub async fn fetch_all(
+        repo: &ForgejoRepo,
+        config: &ForgeConfig,
+    ) -> Result<Vec<ForgejoRelease>> {
+        let token = token(config);
+        let client = reqwest::Client::new();
+        let mut releases: Vec<ForgejoRelease> = fetch_all_pages("Forgejo", |page| {
+            let request = client
+                .get(repo.releases_url())
+                .query(&[("limit", PER_PAGE), ("page", page)]);
+            match &token {
+                Some(token) => request.header("Authorization", format!("token {token}")),
+                None => request,
+            }
+        })
+        .await?;
+        // Drafts are only visible with a token, and aren't meant to be seen yet
+        releases.retain(|r| !r.draft);
+        Ok(releases)
+    }
+
+    pub fn date(&self) -> &str {
+        self.published_at.as_deref().unwrap_or(&self.created_at)
+    }
+
+    pub fn has_dist_manifest(&self) -> bool {
+        self.assets.iter().any(|a| a.name == "dist-manifest.json")
+    }
+
+    pub fn asset_url<'a>(&'a self, asset_name: &'a str) -> Option<&'a str> {
+        self.assets
+            .iter()
+            .find(|a| a.name == asset_name)
+            .map(|a| a.browser_download_url.as_str())
+    }
+
+    /// Download one of the release's files as text, if it has one by that name
+    ///
+    /// Files of private repositories need the token, so it's sent along if there is one.
+    pub async fn fetch_asset(
+        &self,
+        asset_name: &str,
+        config: &ForgeConfig,
+    ) -> Result<Option<String>> {
+        let Some(url) = self.asset_url(asset_name) else {
+            return Ok(None);
+        };
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(token) = token(config) {
+            request = request.header("Authorization", format!("token {token}"));
+        }
+        Ok(Some(http_cache::send(request).await?.text()))
+    }
+}
+
+/// The access token to send with requests, if the user gave us one
+fn token(config: &ForgeConfig) -> Option<String> {
+    let token_env = config.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV);
+    std::env::var(token_env)
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+impl ReleaseArtifacts {
+    pub fn add_forgejo(&mut self, release: &ForgejoRelease) {
+        for asset in &release.assets {
+            let file = File {
+                name: asset.name.clone(),
+                download_url: asset.browser_download_url.clone(),
+                view_path: None,
+                checksum_file: None,
+                signature: None,
+                local_path: None,
+                infer: true,
+            };
+            self.add_file(file);
+        }
+    }
+}
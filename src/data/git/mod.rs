@@ -0,0 +1,123 @@
+use std::process::Command;
+
+use camino::Utf8Path;
+use serde::Serialize;
+
+use super::{compare_tags_by_version, parse_version, strip_tag_pattern};
+
+/// A release made from a version tag in the local git repository
+#[derive(Clone, Debug, Serialize)]
+pub struct GitRelease {
+    pub tag_name: String,
+    /// When the tag was made (or its commit, for lightweight tags)
+    pub date: Option<String>,
+    /// The tag's message, if it's an annotated tag
+    pub body: Option<String>,
+    pub prerelease: bool,
+}
+
+impl GitRelease {
+    /// Read all the version tags in the git repository at `dir`, from newest to oldest
+    ///
+    /// Tags are version tags if they parse as versions, or match `tag_pattern` if there is one.
+    /// If this isn't a git repository (or git isn't installed) there are simply no tags.
+    pub fn load_all(dir: &Utf8Path, tag_pattern: Option<&str>) -> Vec<GitRelease> {
+        // One record per tag, with NUL-separated fields. The message is asked for as its subject
+        // and body, since `%(contents)` would include the signature of a signed tag.
+        let format = "%(refname:short)%00%(objecttype)%00%(creatordate:iso-strict)\
+                      %00%(contents:subject)%00%(contents:body)%1e";
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["for-each-ref", &format!("--format={format}"), "refs/tags"])
+            .output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                tracing::debug!(
+                    "Couldn't list git tags: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                return vec![];
+            }
+            Err(e) => {
+                tracing::debug!("Couldn't run git to list tags: {e}");
+                return vec![];
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut releases = stdout
+            .split('\x1e')
+            .filter_map(|record| {
+                let mut fields = record.trim_start_matches('\n').splitn(5, '\0');
+                let tag_name = fields.next()?.to_owned();
+                let object_type = fields.next()?;
+                let date = fields.next()?;
+                let subject = fields.next().unwrap_or_default().trim();
+                let body = fields.next().unwrap_or_default().trim();
+                let contents = if body.is_empty() {
+                    subject.to_owned()
+                } else {
+                    format!("{subject}\n\n{body}")
+                };
+
+                let version = match tag_pattern {
+                    Some(pattern) => parse_version(strip_tag_pattern(&tag_name, pattern)?),
+                    None => parse_version(&tag_name),
+                };
+                if version.is_none() && tag_pattern.is_none() {
+                    return None;
+                }
+                Some(GitRelease {
+                    prerelease: version.is_some_and(|v| !v.pre.is_empty()),
+                    date: (!date.is_empty()).then(|| date.to_owned()),
+                    // Lightweight tags point straight at a commit, whose message isn't release notes
+                    body: (object_type == "tag" && !contents.is_empty()).then_some(contents),
+                    tag_name,
+                })
+            })
+            .collect::<Vec<_>>();
+        releases.sort_by(|a, b| compare_tags_by_version(&a.tag_name, &b.tag_name));
+        releases
+    }
+}
+
+/// The authors of the commits between two tags in the git repository at `dir`, sorted by name
+///
+/// Without a `from_tag`, this is everyone who contributed up to `to_tag`. If either tag
+/// doesn't exist (or this isn't a git repository), there are simply no contributors.
+pub fn contributors(dir: &Utf8Path, from_tag: Option<&str>, to_tag: &str) -> Vec<String> {
+    let range = match from_tag {
+        Some(from_tag) => format!("{from_tag}..{to_tag}"),
+        None => to_tag.to_owned(),
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["log", "--format=%aN", &range, "--"])
+        .output();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            tracing::debug!(
+                "Couldn't list contributors for {range}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return vec![];
+        }
+        Err(e) => {
+            tracing::debug!("Couldn't run git to list contributors: {e}");
+            return vec![];
+        }
+    };
+
+    let mut names = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    names.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()).then(a.cmp(b)));
+    names.dedup();
+    names
+}
@@ -1,11 +1,35 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::ForgeConfig;
 use crate::errors::*;
+use crate::http_cache;
 
 use axoasset::SourceFile;
 use axoproject::GithubRepo;
 use chrono::DateTime;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 
 use super::artifacts::{File, ReleaseArtifacts};
+use super::split_repo_url;
+
+/// How many releases to ask for per page (the maximum GitHub allows)
+const PER_PAGE: usize = 100;
+/// The token variable we read if the user doesn't pick one
+const DEFAULT_TOKEN_ENV: &str = "GITHUB_TOKEN";
+/// How many times we retry a request that got rate limited
+const MAX_RETRIES: u32 = 3;
+/// The longest we're willing to wait for a rate limit to reset
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// A GitHub repository, and the API we can ask about it
+/// (either github.com's, or a GitHub Enterprise Server's)
+#[derive(Clone, Debug)]
+pub struct GithubApi {
+    /// The API's URL, like "https://api.github.com"
+    pub api_url: String,
+    pub repo: GithubRepo,
+}
 
 /// From the GitHub Rest API
 /// as documented here: <https://docs.github.com/en/rest/releases/releases?apiVersion=2022-11-28>
@@ -46,39 +70,94 @@ pub struct GithubReleaseAsset {
     pub browser_download_url: String,
 }
 
+impl GithubApi {
+    /// Get the repository from a repository URL (https or ssh)
+    ///
+    /// Repositories on github.com use api.github.com, and repositories anywhere else
+    /// are assumed to be on a GitHub Enterprise Server (with its API under "/api/v3").
+    /// Either can be overridden with the instance URL in `components.github`.
+    pub fn from_url(repo_url: &str, config: &ForgeConfig) -> Result<Self> {
+        let (host, path) = split_repo_url(repo_url)?;
+        let mut segments = path.split('/');
+        let (Some(owner), Some(name), None) = (segments.next(), segments.next(), segments.next())
+        else {
+            return Err(OrandaError::UnknownRepoStyle {
+                url: repo_url.to_owned(),
+            });
+        };
+        let api_url = match &config.base_url {
+            Some(base_url) => format!("{}/api/v3", base_url.trim_end_matches('/')),
+            None if host == "https://github.com" => "https://api.github.com".to_owned(),
+            None => format!("{host}/api/v3"),
+        };
+        Ok(GithubApi {
+            api_url,
+            repo: GithubRepo {
+                owner: owner.to_owned(),
+                name: name.to_owned(),
+            },
+        })
+    }
+
+    /// The URL of the repository's releases API
+    fn releases_url(&self) -> String {
+        format!(
+            "{}/repos/{}/{}/releases",
+            self.api_url, self.repo.owner, self.repo.name
+        )
+    }
+}
+
 impl GithubRelease {
-    pub async fn fetch_all(repo: &GithubRepo) -> Result<Vec<GithubRelease>> {
-        let request = octolotl::request::Releases::new(&repo.owner, &repo.name);
-        match octolotl::Request::send(&request, true).await {
-            Ok(r) => {
-                let res: serde_json::Value = serde_json::from_str(&r.text().await?)?;
-                let pretty_response = serde_json::to_string_pretty(&res)?;
-                let releases = SourceFile::new("", pretty_response)
-                    .deserialize_json::<Vec<GithubRelease>>()?;
-
-                // If DEBUG_DATA_CLAMP_DATE is set, discard entries that claim to be from after that date
-                let clamp = std::env::var("DEBUG_DATA_CLAMP_DATE").ok();
-                let parsed_clamp = clamp.map(|t| {
-                    DateTime::parse_from_rfc3339(&t).expect("failed to parse DEBUG_DATA_CLAMP_DATE")
-                });
-                let releases = releases
-                    .into_iter()
-                    .filter(|r| {
-                        let Some(clamp) = parsed_clamp else {
-                            return true;
-                        };
-                        let Ok(timestamp) = DateTime::parse_from_rfc3339(&r.created_at) else {
-                            return true;
-                        };
-
-                        timestamp <= clamp
-                    })
-                    .collect();
-
-                Ok(releases)
+    pub async fn fetch_all(api: &GithubApi, config: &ForgeConfig) -> Result<Vec<GithubRelease>> {
+        let (token_env, token) = token(config);
+        let client = reqwest::Client::new();
+        let mut releases = vec![];
+        for page in 1.. {
+            let mut request = client
+                .get(api.releases_url())
+                .query(&[("per_page", PER_PAGE), ("page", page)])
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", "oranda");
+            if let Some(token) = &token {
+                request = request.header("Authorization", format!("Bearer {token}"));
+            }
+            let token_env = token_env.to_owned();
+            let response = http_cache::fetch(request, |request| async move {
+                send_with_retries(request)
+                    .await
+                    .map_err(|details| OrandaError::GithubReleasesFetchError { token_env, details })
+            })
+            .await?;
+            let page =
+                SourceFile::new("", response.text()).deserialize_json::<Vec<GithubRelease>>()?;
+            let is_last = page.len() < PER_PAGE;
+            releases.extend(page);
+            if is_last {
+                break;
             }
-            Err(e) => Err(OrandaError::GithubReleasesFetchError { details: e }),
         }
+
+        // If DEBUG_DATA_CLAMP_DATE is set, discard entries that claim to be from after that date
+        let clamp = std::env::var("DEBUG_DATA_CLAMP_DATE").ok();
+        let parsed_clamp = clamp.map(|t| {
+            DateTime::parse_from_rfc3339(&t).expect("failed to parse DEBUG_DATA_CLAMP_DATE")
+        });
+        let releases = releases
+            .into_iter()
+            .filter(|r| {
+                let Some(clamp) = parsed_clamp else {
+                    return true;
+                };
+                let Ok(timestamp) = DateTime::parse_from_rfc3339(&r.created_at) else {
+                    return true;
+                };
+
+                timestamp <= clamp
+            })
+            .collect();
+
+        Ok(releases)
     }
 
     pub fn has_dist_manifest(&self) -> bool {
@@ -94,25 +173,44 @@ impl GithubRelease {
         None
     }
 
-    pub fn repo_has_releases(repo: &GithubRepo) -> Result<bool> {
-        if let Ok(releases) =
-            tokio::runtime::Handle::current().block_on(GithubRelease::fetch_all(repo))
-        {
-            if releases.is_empty() {
-                Ok(false)
-            } else {
-                Ok(true)
-            }
-        } else {
-            let warning = OrandaError::ReleasesCheckFailed {
-                repo: repo.to_string(),
-            };
-            eprintln!("{:?}", miette::Report::new(warning));
-            Ok(false)
+    /// Download one of the release's files as text, if it has one by that name
+    ///
+    /// This goes through the API (rather than the browser download URL) with the token,
+    /// so it works for private repositories and counts against the token's rate limit.
+    pub async fn fetch_asset(
+        &self,
+        asset_name: &str,
+        config: &ForgeConfig,
+    ) -> Result<Option<String>> {
+        let Some(asset) = self.assets.iter().find(|a| a.name == asset_name) else {
+            return Ok(None);
+        };
+        let (token_env, token) = token(config);
+        let mut request = reqwest::Client::new()
+            .get(&asset.url)
+            .header("Accept", "application/octet-stream")
+            .header("User-Agent", "oranda");
+        if let Some(token) = &token {
+            request = request.header("Authorization", format!("Bearer {token}"));
         }
+        let token_env = token_env.to_owned();
+        let response = http_cache::fetch(request, |request| async move {
+            send_with_retries(request)
+                .await
+                .map_err(|details| OrandaError::GithubReleasesFetchError { token_env, details })
+        })
+        .await?;
+        Ok(Some(response.text()))
     }
 }
 
+/// The token variable to read, and the token in it (if there is one)
+fn token(config: &ForgeConfig) -> (&str, Option<String>) {
+    let token_env = config.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV);
+    let token = std::env::var(token_env).ok().filter(|t| !t.is_empty());
+    (token_env, token)
+}
+
 impl ReleaseArtifacts {
     pub fn add_github(&mut self, release: &GithubRelease) {
         for asset in &release.assets {
@@ -122,9 +220,66 @@ impl ReleaseArtifacts {
                 // The rest of these are filled in later
                 view_path: None,
                 checksum_file: None,
+                signature: None,
+                local_path: None,
                 infer: true,
             };
             self.add_file(file);
         }
     }
 }
+
+/// Send a request, waiting and trying again if GitHub says we're being rate limited
+async fn send_with_retries(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let mut attempt = 0;
+    loop {
+        let response = request
+            .try_clone()
+            .expect("requests without a streaming body can be cloned")
+            .send()
+            .await?;
+        match rate_limit_wait(&response, attempt) {
+            Some(wait) if attempt < MAX_RETRIES && wait <= MAX_RETRY_WAIT => {
+                tracing::warn!(
+                    "GitHub is rate limiting us, trying again in {} seconds...",
+                    wait.as_secs()
+                );
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            _ => return response.error_for_status(),
+        }
+    }
+}
+
+/// If this response says we're being rate limited, how long we should wait to try again
+///
+/// GitHub tells us this with either a retry-after header (secondary rate limits) or
+/// an exhausted x-ratelimit-remaining (primary rate limits). If it doesn't say, we back off
+/// exponentially.
+fn rate_limit_wait(response: &reqwest::Response, attempt: u32) -> Option<Duration> {
+    let status = response.status();
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::FORBIDDEN {
+        return None;
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+    if let Some(seconds) = header("retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        let reset = header("x-ratelimit-reset")?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        return Some(Duration::from_secs(reset.saturating_sub(now)));
+    }
+    // A 403 with no rate limit headers is just a permissions problem
+    (status == StatusCode::TOO_MANY_REQUESTS).then(|| Duration::from_secs(1 << attempt))
+}
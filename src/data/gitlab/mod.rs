@@ -0,0 +1,177 @@
+use crate::config::ForgeConfig;
+use crate::errors::*;
+use crate::http_cache;
+
+use serde::{Deserialize, Serialize};
+
+use super::artifacts::{File, ReleaseArtifacts};
+use super::{fetch_all_pages, parse_version, split_repo_url};
+
+/// How many releases to ask for per page (the maximum GitLab allows)
+const PER_PAGE: usize = 100;
+/// The token variable we read if the user doesn't pick one
+const DEFAULT_TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// A GitLab project, possibly on a self-hosted instance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GitlabRepo {
+    /// The instance's URL, like "https://gitlab.com"
+    pub base_url: String,
+    /// The full path of the project, like "group/subgroup/project"
+    pub path: String,
+}
+
+/// From the GitLab REST API
+/// as documented here: <https://docs.gitlab.com/ee/api/releases/>
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GitlabRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub released_at: Option<String>,
+    #[serde(default)]
+    pub upcoming_release: bool,
+    pub assets: GitlabReleaseAssets,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GitlabReleaseAssets {
+    #[serde(default)]
+    pub links: Vec<GitlabReleaseLink>,
+}
+
+/// A file (or other link) attached to a release
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GitlabReleaseLink {
+    pub name: String,
+    pub url: String,
+    pub direct_asset_url: Option<String>,
+    pub link_type: Option<String>,
+}
+
+impl GitlabRepo {
+    /// Get the project from a repository URL (https or ssh)
+    ///
+    /// The instance URL defaults to the repository's host, but can be overridden.
+    pub fn from_url(repo_url: &str, config: &ForgeConfig) -> Result<Self> {
+        let (host, path) = split_repo_url(repo_url)?;
+        if !path.contains('/') {
+            return Err(OrandaError::UnknownRepoStyle {
+                url: repo_url.to_owned(),
+            });
+        }
+        let base_url = config.base_url.clone().unwrap_or(host);
+        Ok(GitlabRepo {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            path,
+        })
+    }
+
+    /// The URL of the project's releases API
+    fn releases_url(&self) -> String {
+        let mut encoded_path = String::new();
+        url_escape::encode_component_to_string(&self.path, &mut encoded_path);
+        format!("{}/api/v4/projects/{encoded_path}/releases", self.base_url)
+    }
+}
+
+impl GitlabRelease {
+    pub async fn fetch_all(repo: &GitlabRepo, config: &ForgeConfig) -> Result<Vec<GitlabRelease>> {
+        let token = token(config);
+        let client = reqwest::Client::new();
+        fetch_all_pages("GitLab", |page| {
+            let request = client
+                .get(repo.releases_url())
+                .query(&[("per_page", PER_PAGE), ("page", page)]);
+            match &token {
+                Some(token) => request.header("PRIVATE-TOKEN", token),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    /// GitLab doesn't have prereleases, so upcoming releases and semver prereleases count
+    pub fn is_prerelease(&self) -> bool {
+        self.upcoming_release || parse_version(&self.tag_name).is_some_and(|v| !v.pre.is_empty())
+    }
+
+    pub fn date(&self) -> &str {
+        self.released_at.as_deref().unwrap_or(&self.created_at)
+    }
+
+    pub fn has_dist_manifest(&self) -> bool {
+        self.assets
+            .links
+            .iter()
+            .any(|a| a.name == "dist-manifest.json")
+    }
+
+    pub fn asset_url<'a>(&'a self, asset_name: &'a str) -> Option<&'a str> {
+        self.assets
+            .links
+            .iter()
+            .find(|a| a.name == asset_name)
+            .map(GitlabReleaseLink::download_url)
+    }
+
+    /// Download one of the release's files as text, if it has one by that name
+    ///
+    /// Files of private projects need the token, so it's sent along if there is one.
+    pub async fn fetch_asset(
+        &self,
+        asset_name: &str,
+        config: &ForgeConfig,
+    ) -> Result<Option<String>> {
+        let Some(url) = self.asset_url(asset_name) else {
+            return Ok(None);
+        };
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(token) = token(config) {
+            request = request.header("PRIVATE-TOKEN", token);
+        }
+        Ok(Some(http_cache::send(request).await?.text()))
+    }
+}
+
+/// The access token to send with requests, if the user gave us one
+fn token(config: &ForgeConfig) -> Option<String> {
+    let token_env = config.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV);
+    std::env::var(token_env)
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+impl GitlabReleaseLink {
+    /// The URL to download the file from
+    ///
+    /// Prefer the permanent "direct asset" URL when there is one
+    pub fn download_url(&self) -> &str {
+        self.direct_asset_url.as_deref().unwrap_or(&self.url)
+    }
+}
+
+impl ReleaseArtifacts {
+    pub fn add_gitlab(&mut self, release: &GitlabRelease) {
+        for link in &release.assets.links {
+            // Links can point at things other than files (e.g. "runbook" links)
+            if matches!(link.link_type.as_deref(), Some("runbook")) {
+                continue;
+            }
+            let file = File {
+                name: link.name.clone(),
+                download_url: link.download_url().to_owned(),
+                view_path: None,
+                checksum_file: None,
+                signature: None,
+                local_path: None,
+                infer: true,
+            };
+            // Links can share names, and we can only keep track of one of them
+            if self.file_idx(&file.name).is_none() {
+                self.add_file(file);
+            }
+        }
+    }
+}
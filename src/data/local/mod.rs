@@ -0,0 +1,184 @@
+use crate::config::LocalReleasesConfig;
+use crate::errors::*;
+
+use axoasset::LocalAsset;
+use axoproject::GithubRepo;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_dist_schema::DistManifest;
+use serde::Serialize;
+
+use super::artifacts::{File, ReleaseArtifacts};
+use super::cargo_dist::MANIFEST_FILENAME;
+use super::{compare_tags_by_version, parse_version, Release};
+
+/// A release read from a directory on disk (e.g. cargo-dist's target/distrib/)
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalRelease {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub prerelease: bool,
+    /// The directory this release was read from
+    pub path: Utf8PathBuf,
+    pub assets: Vec<LocalReleaseAsset>,
+    #[serde(skip)]
+    pub manifest: Option<DistManifest>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalReleaseAsset {
+    pub name: String,
+    pub download_url: String,
+    #[serde(skip)]
+    pub path: Utf8PathBuf,
+}
+
+impl LocalRelease {
+    /// Read all the releases in the configured directories, from newest to oldest
+    ///
+    /// Releases without a tag of their own (a lone target/distrib/ without an
+    /// announcement tag in its dist-manifest.json) are named after `fallback_tag`.
+    pub fn load_all(
+        config: &LocalReleasesConfig,
+        repo: Option<&GithubRepo>,
+        fallback_tag: &str,
+    ) -> Result<Vec<LocalRelease>> {
+        let mut releases = vec![];
+        let mut found_any = false;
+        for path in &config.paths {
+            let dir = Utf8PathBuf::from(path);
+            if !dir.is_dir() {
+                continue;
+            }
+            found_any = true;
+
+            // A directory with a dist-manifest.json is a release, otherwise
+            // it's a directory of releases named after their tags (if it has subdirs)
+            let subdirs = subdirectories(&dir)?;
+            if dir.join(MANIFEST_FILENAME).exists() || subdirs.is_empty() {
+                releases.push(Self::load(config, repo, &dir, None, fallback_tag)?);
+            } else {
+                for subdir in subdirs {
+                    let tag = subdir.file_name().unwrap_or(fallback_tag).to_owned();
+                    releases.push(Self::load(config, repo, &subdir, Some(tag), fallback_tag)?);
+                }
+            }
+        }
+        if !found_any {
+            return Err(OrandaError::LocalReleasesNotFound {
+                paths: config.paths.join(", "),
+            });
+        }
+
+        releases.sort_by(|a, b| compare_tags_by_version(&a.tag_name, &b.tag_name));
+        Ok(releases)
+    }
+
+    fn load(
+        config: &LocalReleasesConfig,
+        repo: Option<&GithubRepo>,
+        dir: &Utf8Path,
+        tag: Option<String>,
+        fallback_tag: &str,
+    ) -> Result<LocalRelease> {
+        let manifest_path = dir.join(MANIFEST_FILENAME);
+        let manifest = if manifest_path.exists() {
+            let contents = LocalAsset::load_string(&manifest_path)?;
+            Release::parse_manifest(contents, manifest_path.as_str())
+        } else {
+            None
+        };
+        let tag_name = tag
+            .or_else(|| manifest.as_ref().and_then(|m| m.announcement_tag.clone()))
+            .unwrap_or_else(|| fallback_tag.to_owned());
+        let prerelease = manifest
+            .as_ref()
+            .is_some_and(|m| m.announcement_is_prerelease)
+            || parse_version(&tag_name).is_some_and(|v| !v.pre.is_empty());
+
+        let mut assets = vec![];
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_owned();
+            let download_url = download_url(config, repo, &tag_name, entry.path())?;
+            assets.push(LocalReleaseAsset {
+                name,
+                download_url,
+                path: entry.path().to_owned(),
+            });
+        }
+        assets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(LocalRelease {
+            name: manifest.as_ref().and_then(|m| m.announcement_title.clone()),
+            body: manifest
+                .as_ref()
+                .and_then(|m| m.announcement_changelog.clone()),
+            tag_name,
+            prerelease,
+            path: dir.to_owned(),
+            assets,
+            manifest,
+        })
+    }
+}
+
+impl ReleaseArtifacts {
+    pub fn add_local(&mut self, release: &LocalRelease) {
+        for asset in &release.assets {
+            let file = File {
+                name: asset.name.clone(),
+                download_url: asset.download_url.clone(),
+                view_path: None,
+                checksum_file: None,
+                signature: None,
+                local_path: Some(asset.path.clone()),
+                infer: true,
+            };
+            self.add_file(file);
+        }
+    }
+}
+
+/// Get all the directories directly inside this one, sorted by name
+fn subdirectories(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut subdirs = vec![];
+    for entry in dir.read_dir_utf8()? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            subdirs.push(entry.into_path());
+        }
+    }
+    subdirs.sort();
+    Ok(subdirs)
+}
+
+/// Get the URL a file will be downloadable from once it's published
+fn download_url(
+    config: &LocalReleasesConfig,
+    repo: Option<&GithubRepo>,
+    tag: &str,
+    path: &Utf8Path,
+) -> Result<String> {
+    let file = path.file_name().unwrap_or_default();
+    let template = match (&config.download_url, repo) {
+        (Some(template), _) => template.clone(),
+        (None, Some(repo)) => format!(
+            "https://github.com/{}/{}/releases/download/{{tag}}/{{file}}",
+            repo.owner, repo.name
+        ),
+        (None, None) => {
+            // Nowhere to publish it to, so just link to the file itself
+            let path = path.canonicalize_utf8()?;
+            return Ok(format!("file://{path}"));
+        }
+    };
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    Ok(template
+        .replace("{tag}", tag)
+        .replace("{version}", version)
+        .replace("{file}", file))
+}
@@ -1,21 +1,36 @@
-use crate::config::{ArtifactsConfig, ProjectConfig};
+use crate::config::{ArtifactsConfig, ForgeConfig, LocalReleasesConfig, ProjectConfig};
 use crate::data::axodotdev::AxoRelease;
-use crate::data::github::GithubRelease;
-use crate::data::release::CurrentStateRelease;
-use crate::data::release::ReleaseSource;
+use crate::data::changelog::ChangelogEntry;
+use crate::data::forgejo::{ForgejoRelease, ForgejoRepo};
+use crate::data::git::GitRelease;
+use crate::data::github::{GithubApi, GithubRelease};
+use crate::data::gitlab::{GitlabRelease, GitlabRepo};
+use crate::data::local::LocalRelease;
 use crate::errors::*;
+use crate::http_cache;
 
 pub mod artifacts;
 pub mod axodotdev;
 pub mod cargo_dist;
+pub mod changelog;
+pub mod forgejo;
 pub mod funding;
+pub mod git;
 pub mod github;
+pub mod gitlab;
+pub mod local;
 mod release;
+pub mod repo_links;
 pub mod workspaces;
 
-pub use release::Release;
+pub use release::{CurrentStateRelease, Release, ReleaseSource};
 
+use axoasset::SourceFile;
 use axoproject::GithubRepo;
+use camino::Utf8Path;
+use reqwest::RequestBuilder;
+use semver::Version;
+use serde::de::DeserializeOwned;
 
 #[derive(Debug)]
 pub struct Context {
@@ -42,11 +57,8 @@ impl Context {
         project_config: &ProjectConfig,
         artifacts_config: Option<&ArtifactsConfig>,
     ) -> Result<Self> {
-        let releases = tokio::runtime::Handle::current().block_on(Self::make_current_release(
-            None,
-            project_config,
-            artifacts_config,
-        ))?;
+        let releases = tokio::runtime::Handle::current()
+            .block_on(Self::make_current_release(project_config, artifacts_config))?;
         Ok(Self::with_releases(
             None,
             releases,
@@ -57,14 +69,15 @@ impl Context {
     /// Get releases using github
     pub fn new_github(
         repo_url: &str,
+        github_config: &ForgeConfig,
         project_config: &ProjectConfig,
         artifacts_config: Option<&ArtifactsConfig>,
     ) -> Result<Self> {
-        let repo = GithubRepo::from_url(repo_url)?;
-        let mut releases = Self::fetch_all_github_releases(&repo, artifacts_config)?;
+        let api = GithubApi::from_url(repo_url, github_config)?;
+        let mut releases = Self::fetch_all_github_releases(&api, github_config, artifacts_config)?;
+        let repo = api.repo;
         if releases.is_empty() {
-            releases = tokio::runtime::Handle::current().block_on(Self::make_current_release(
-                Some(&repo),
+            releases = tokio::runtime::Handle::current().block_on(Self::make_fallback_releases(
                 project_config,
                 artifacts_config,
             ))?;
@@ -77,6 +90,53 @@ impl Context {
         ))
     }
 
+    /// Get releases using GitLab
+    pub fn new_gitlab(
+        repo_url: &str,
+        gitlab_config: &ForgeConfig,
+        project_config: &ProjectConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Self> {
+        let repo = GitlabRepo::from_url(repo_url, gitlab_config)?;
+        let mut releases = Self::fetch_all_gitlab_releases(&repo, gitlab_config, artifacts_config)?;
+        if releases.is_empty() {
+            releases = tokio::runtime::Handle::current().block_on(Self::make_fallback_releases(
+                project_config,
+                artifacts_config,
+            ))?;
+        }
+        Ok(Self::with_releases(
+            None,
+            releases,
+            artifacts_config,
+            project_config,
+        ))
+    }
+
+    /// Get releases using Forgejo (or Gitea)
+    pub fn new_forgejo(
+        repo_url: &str,
+        forgejo_config: &ForgeConfig,
+        project_config: &ProjectConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Self> {
+        let repo = ForgejoRepo::from_url(repo_url, forgejo_config)?;
+        let mut releases =
+            Self::fetch_all_forgejo_releases(&repo, forgejo_config, artifacts_config)?;
+        if releases.is_empty() {
+            releases = tokio::runtime::Handle::current().block_on(Self::make_fallback_releases(
+                project_config,
+                artifacts_config,
+            ))?;
+        }
+        Ok(Self::with_releases(
+            None,
+            releases,
+            artifacts_config,
+            project_config,
+        ))
+    }
+
     /// Get releases using axo Releases
     pub fn new_axodotdev(
         package_name: &str,
@@ -88,8 +148,7 @@ impl Context {
         let mut releases =
             Self::fetch_all_axodotdev_releases(package_name, &repo, artifacts_config)?;
         if releases.is_empty() {
-            releases = tokio::runtime::Handle::current().block_on(Self::make_current_release(
-                Some(&repo),
+            releases = tokio::runtime::Handle::current().block_on(Self::make_fallback_releases(
                 project_config,
                 artifacts_config,
             ))?;
@@ -102,6 +161,61 @@ impl Context {
         ))
     }
 
+    /// Get releases from the version tags in the local git repository
+    pub fn new_git(
+        repo_url: Option<&str>,
+        project_config: &ProjectConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Self> {
+        let repo = repo_url.and_then(|url| GithubRepo::from_url(url).ok());
+        let releases = tokio::runtime::Handle::current().block_on(Self::make_fallback_releases(
+            project_config,
+            artifacts_config,
+        ))?;
+        Ok(Self::with_releases(
+            repo,
+            releases,
+            artifacts_config,
+            project_config,
+        ))
+    }
+
+    /// Make a Context with an entry for each version heading in a changelog file
+    pub fn new_changelog_file(path: &Utf8Path, project_config: &ProjectConfig) -> Result<Self> {
+        let entries = ChangelogEntry::load_all(path)?;
+        let releases =
+            tokio::runtime::Handle::current().block_on(futures_util::future::try_join_all(
+                entries
+                    .into_iter()
+                    .map(|entry| Release::new(ReleaseSource::Changelog(entry), None)),
+            ))?;
+        Ok(Self::with_releases(None, releases, None, project_config))
+    }
+
+    /// Get releases from directories on disk
+    pub fn new_local(
+        local_config: &LocalReleasesConfig,
+        repo_url: Option<&str>,
+        project_config: &ProjectConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Self> {
+        // Download links can point at GitHub Releases, but any other kind of repo is fine too
+        let repo = repo_url.and_then(|url| GithubRepo::from_url(url).ok());
+        let fallback_tag = project_config.version.as_deref().unwrap_or("current");
+        let local_releases = LocalRelease::load_all(local_config, repo.as_ref(), fallback_tag)?;
+        let releases = tokio::runtime::Handle::current().block_on(
+            futures_util::future::try_join_all(local_releases.into_iter().map(|local_release| {
+                Release::new(ReleaseSource::Local(local_release), artifacts_config)
+            })),
+        )?;
+        Ok(Self::with_releases(
+            repo,
+            releases,
+            artifacts_config,
+            project_config,
+        ))
+    }
+
     /// Get the latest release, if it exists
     pub fn latest(&self) -> Option<&Release> {
         self.latest_release.and_then(|idx| self.releases.get(idx))
@@ -115,23 +229,65 @@ impl Context {
 
     /// Fetch and process all the Github Releases to produce a final result
     pub fn fetch_all_github_releases(
-        repo: &GithubRepo,
+        api: &GithubApi,
+        github_config: &ForgeConfig,
         artifacts_config: Option<&ArtifactsConfig>,
     ) -> Result<Vec<Release>> {
-        let gh_releases =
-            tokio::runtime::Handle::current().block_on(GithubRelease::fetch_all(repo))?;
+        let gh_releases = tokio::runtime::Handle::current()
+            .block_on(GithubRelease::fetch_all(api, github_config))?;
         let all = tokio::runtime::Handle::current().block_on(
             futures_util::future::try_join_all(gh_releases.into_iter().map(|gh_release| {
-                Release::new(
+                Release::with_forge(
                     ReleaseSource::Github(gh_release),
-                    Some(repo),
                     artifacts_config,
+                    github_config,
+                )
+            })),
+        )?;
+        Ok(all)
+    }
+
+    /// Fetch and process all the GitLab Releases to produce a final result
+    pub fn fetch_all_gitlab_releases(
+        repo: &GitlabRepo,
+        gitlab_config: &ForgeConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Vec<Release>> {
+        let gitlab_releases = tokio::runtime::Handle::current()
+            .block_on(GitlabRelease::fetch_all(repo, gitlab_config))?;
+        let all = tokio::runtime::Handle::current().block_on(
+            futures_util::future::try_join_all(gitlab_releases.into_iter().map(|gitlab_release| {
+                Release::with_forge(
+                    ReleaseSource::Gitlab(gitlab_release),
+                    artifacts_config,
+                    gitlab_config,
                 )
             })),
         )?;
         Ok(all)
     }
 
+    /// Fetch and process all the Forgejo Releases to produce a final result
+    pub fn fetch_all_forgejo_releases(
+        repo: &ForgejoRepo,
+        forgejo_config: &ForgeConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Vec<Release>> {
+        let forgejo_releases = tokio::runtime::Handle::current()
+            .block_on(ForgejoRelease::fetch_all(repo, forgejo_config))?;
+        let all =
+            tokio::runtime::Handle::current().block_on(futures_util::future::try_join_all(
+                forgejo_releases.into_iter().map(|forgejo_release| {
+                    Release::with_forge(
+                        ReleaseSource::Forgejo(forgejo_release),
+                        artifacts_config,
+                        forgejo_config,
+                    )
+                }),
+            ))?;
+        Ok(all)
+    }
+
     /// Fetch and process all the axo Releases to produce a final result
     pub fn fetch_all_axodotdev_releases(
         package_name: &str,
@@ -142,17 +298,16 @@ impl Context {
             .block_on(AxoRelease::fetch_all(package_name, repo))?;
         let all = tokio::runtime::Handle::current().block_on(
             futures_util::future::try_join_all(axo_releases.into_iter().map(|axo_release| {
-                Release::new(
-                    ReleaseSource::Axodotdev(axo_release),
-                    Some(repo),
-                    artifacts_config,
-                )
+                Release::new(ReleaseSource::Axodotdev(axo_release), artifacts_config)
             })),
         )?;
         Ok(all)
     }
 
-    fn with_releases(
+    /// Make a Context out of already-fetched releases (from newest to oldest)
+    ///
+    /// This is where releases are filtered and the latest one is picked.
+    pub fn with_releases(
         repo: Option<GithubRepo>,
         releases: Vec<Release>,
         artifacts_config: Option<&ArtifactsConfig>,
@@ -160,10 +315,21 @@ impl Context {
     ) -> Self {
         // Walk through all the releases (from newest to oldest) to find the latest ones
         //
-        // FIXME?: I think this is essentially deferring to Release Date over Version Number.
-        // In most cases those things are in agreement, but anyone who does patch releases
-        // of older versions will have a more chaotic history. When we have more robust
-        // handling of release tags we should try to sort/filter this better.
+        // Whenever we can get a version out of the tag, "latest" means "highest version",
+        // so patch releases of older lines don't get picked over newer lines. Releases
+        // we can't get a version from only win if nothing else in their category has one,
+        // in which case we fall back to preferring the newest.
+        let tag_pattern = artifacts_config.and_then(|a| a.tag_pattern.as_deref());
+        // If there's a tag pattern, releases that don't match it aren't for this site at all
+        let releases: Vec<Release> = match tag_pattern {
+            Some(pattern) => releases
+                .into_iter()
+                .filter(|release| {
+                    strip_tag_pattern(release.source.version_tag(), pattern).is_some()
+                })
+                .collect(),
+            None => releases,
+        };
         let mut has_prereleases = false;
         let mut has_artifacts = false;
         let mut warned = false;
@@ -173,32 +339,36 @@ impl Context {
         let mut latest_prerelease = None;
 
         for (idx, release) in releases.iter().enumerate() {
+            let tag = release.source.version_tag();
             // If we're matching package names to releases, we want to skip any releases
             // which tag name doesn't contain the project name
             if artifacts_config
                 .map(|a| a.match_package_names)
                 .unwrap_or(false)
-                && !release.source.version_tag().contains(&project_config.name)
+                && !tag.contains(&project_config.name)
             {
                 continue;
             }
+            let version = match tag_pattern {
+                Some(pattern) => strip_tag_pattern(tag, pattern).and_then(parse_version),
+                None => parse_version(tag),
+            };
 
             // Make note of whether anything has artifacts
             if release.has_installers() {
                 has_artifacts = true;
             }
 
-            let is_prerelease = release.source.is_prerelease();
+            let is_prerelease = release.source.is_prerelease()
+                || version.as_ref().is_some_and(|v| !v.pre.is_empty());
             // Make note of whether we've found prereleases or stable releases yet
             if is_prerelease {
                 if !has_prereleases {
                     has_prereleases = true;
                 }
-                if latest_prerelease.is_none() {
-                    latest_prerelease = Some(idx);
-                }
-            } else if latest_stable_release.is_none() {
-                latest_stable_release = Some(idx);
+                consider_latest(&mut latest_prerelease, idx, &version);
+            } else {
+                consider_latest(&mut latest_stable_release, idx, &version);
             }
 
             // Special handling of dist-manifest.json
@@ -207,11 +377,9 @@ impl Context {
                     // cargo-dist is enabled, so we want to find the latest stable release
                     // or, failing that, the latest prerelease.
                     if is_prerelease {
-                        if latest_dist_prerelease.is_none() {
-                            latest_dist_prerelease = Some(idx);
-                        }
-                    } else if latest_dist_stable_release.is_none() {
-                        latest_dist_stable_release = Some(idx);
+                        consider_latest(&mut latest_dist_prerelease, idx, &version);
+                    } else {
+                        consider_latest(&mut latest_dist_stable_release, idx, &version);
                     }
                 } else if !warned {
                     // We found a dist-manifest but they didn't enable cargo-dist support, encourage them to do so
@@ -222,6 +390,11 @@ impl Context {
             }
         }
 
+        let latest_dist_stable_release = latest_dist_stable_release.map(|(idx, _)| idx);
+        let latest_dist_prerelease = latest_dist_prerelease.map(|(idx, _)| idx);
+        let latest_stable_release = latest_stable_release.map(|(idx, _)| idx);
+        let latest_prerelease = latest_prerelease.map(|(idx, _)| idx);
+
         // If we found a stable cargo-dist release, but there's even newer stable releases
         // that don't use cargo-dist, we're going to prefer the cargo-dist one, but we should
         // warn the user that things are wonky
@@ -261,8 +434,26 @@ impl Context {
         }
     }
 
+    /// Make releases for when there aren't any hosted ones: one per local git tag if there
+    /// are any, otherwise a faux-release for the current project state
+    async fn make_fallback_releases(
+        project_config: &ProjectConfig,
+        artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Vec<Release>> {
+        let tag_pattern = artifacts_config.and_then(|a| a.tag_pattern.as_deref());
+        let git_releases = GitRelease::load_all(Utf8Path::new("."), tag_pattern);
+        if git_releases.is_empty() {
+            return Self::make_current_release(project_config, artifacts_config).await;
+        }
+        futures_util::future::try_join_all(
+            git_releases
+                .into_iter()
+                .map(|git_release| Release::new(ReleaseSource::Git(git_release), artifacts_config)),
+        )
+        .await
+    }
+
     async fn make_current_release(
-        repo: Option<&GithubRepo>,
         project_config: &ProjectConfig,
         artifacts_config: Option<&ArtifactsConfig>,
     ) -> Result<Vec<Release>> {
@@ -272,10 +463,128 @@ impl Context {
                 date: None,
                 prerelease: false,
             }),
-            repo,
             artifacts_config,
         )
         .await?;
         Ok(vec![release])
     }
 }
+
+/// Update the best release seen so far in some category, if this one is better
+///
+/// Higher versions win, and releases without versions never replace anything
+/// (so among those, the newest one wins).
+fn consider_latest(
+    latest: &mut Option<(usize, Option<Version>)>,
+    idx: usize,
+    version: &Option<Version>,
+) {
+    let is_better = match (&latest, version) {
+        (None, _) => true,
+        (Some((_, None)), Some(_)) => true,
+        (Some((_, Some(best))), Some(version)) => version > best,
+        (Some(_), None) => false,
+    };
+    if is_better {
+        *latest = Some((idx, version.clone()));
+    }
+}
+
+/// Get the part of a tag matched by the `{version}` in a tag pattern like "myapp-v{version}"
+///
+/// Returns None if the tag doesn't match the pattern.
+pub fn strip_tag_pattern<'a>(tag: &'a str, pattern: &str) -> Option<&'a str> {
+    let (prefix, suffix) = pattern.split_once("{version}").unwrap_or((pattern, ""));
+    let version = tag.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    (!version.is_empty()).then_some(version)
+}
+
+/// Parse the version out of a tag like "1.2.3" or "v1.2.3"
+pub fn parse_version(version: &str) -> Option<Version> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    Version::parse(version).ok()
+}
+
+/// Order tags from the highest version to the lowest, with anything that isn't a version after them
+pub(crate) fn compare_tags_by_version(a: &str, b: &str) -> std::cmp::Ordering {
+    parse_version(b)
+        .cmp(&parse_version(a))
+        .then_with(|| a.cmp(b))
+}
+
+/// Split a repository URL (https or ssh) into the URL of its host and the path of the repository
+///
+/// So "git@gitlab.com:group/project.git" becomes ("https://gitlab.com", "group/project").
+/// Links to pages inside the repository ("group/project/-/releases") are trimmed.
+pub(crate) fn split_repo_url(repo_url: &str) -> Result<(String, String)> {
+    let parse_err = || OrandaError::UnknownRepoStyle {
+        url: repo_url.to_owned(),
+    };
+    let (host, path) = if let Some(rest) = repo_url.strip_prefix("git@") {
+        let (host, path) = rest.split_once(':').ok_or_else(parse_err)?;
+        (format!("https://{host}"), path.to_owned())
+    } else {
+        let url = url::Url::parse(repo_url.strip_prefix("git+").unwrap_or(repo_url))?;
+        let host = url.host_str().ok_or_else(parse_err)?;
+        let host = match url.port() {
+            Some(port) => format!("{}://{host}:{port}", url.scheme()),
+            None => format!("{}://{host}", url.scheme()),
+        };
+        (host, url.path().to_owned())
+    };
+    let path = path.split("/-/").next().unwrap_or_default();
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if path.is_empty() {
+        return Err(parse_err());
+    }
+    Ok((host, path.to_owned()))
+}
+
+/// The most pages [`fetch_all_pages`][] will fetch
+const MAX_PAGES: usize = 100;
+
+/// Fetch every page of one of a forge's list APIs (GitLab, Forgejo or Gitea)
+///
+/// `page_request` makes the request for a page (starting at 1). Instances can cap the page
+/// size below what we ask for (Gitea's `MAX_RESPONSE_ITEMS` is often 30), so a short page
+/// doesn't mean it's the last one; we keep going until we get an empty page.
+///
+/// In case a server (or a proxy in front of it) ignores the page number, we also stop when
+/// a page is the same as the one before it, and after [`MAX_PAGES`][] pages regardless.
+pub(crate) async fn fetch_all_pages<T: DeserializeOwned>(
+    forge: &str,
+    page_request: impl Fn(usize) -> RequestBuilder,
+) -> Result<Vec<T>> {
+    let mut items = vec![];
+    let mut previous_page = None;
+    for page in 1..=MAX_PAGES {
+        let forge_name = forge.to_owned();
+        let response = http_cache::fetch(page_request(page), |request| async move {
+            request
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|details| OrandaError::ForgeReleasesFetchError {
+                    forge: forge_name,
+                    details,
+                })
+        })
+        .await?;
+        let text = response.text();
+        if previous_page.as_ref() == Some(&text) {
+            tracing::warn!(
+                "{forge} sent the same page of releases twice, so it probably doesn't support paging"
+            );
+            return Ok(items);
+        }
+        let page = SourceFile::new("", text.clone()).deserialize_json::<Vec<T>>()?;
+        if page.is_empty() {
+            return Ok(items);
+        }
+        items.extend(page);
+        previous_page = Some(text);
+    }
+    tracing::warn!("{forge} has more than {MAX_PAGES} pages of releases, ignoring the rest");
+    Ok(items)
+}
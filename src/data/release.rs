@@ -1,20 +1,31 @@
 use axoasset::SourceFile;
 use cargo_dist_schema::DistManifest;
-use chrono::DateTime;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
 use serde::Serialize;
 
-use crate::config::ArtifactsConfig;
-use crate::data::{cargo_dist, github::GithubRelease, GithubRepo};
+use crate::config::{ArtifactsConfig, ForgeConfig};
+use crate::data::{cargo_dist, github::GithubRelease};
 use crate::errors::*;
+use crate::http_cache;
 
 use super::artifacts::ReleaseArtifacts;
 use super::axodotdev::AxoRelease;
+use super::changelog::ChangelogEntry;
+use super::forgejo::ForgejoRelease;
+use super::git::GitRelease;
+use super::gitlab::GitlabRelease;
+use super::local::LocalRelease;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Debug, Clone)]
 pub enum ReleaseSource {
     Github(GithubRelease),
+    Gitlab(GitlabRelease),
+    Forgejo(ForgejoRelease),
     Axodotdev(AxoRelease),
+    Local(LocalRelease),
+    Git(GitRelease),
+    Changelog(ChangelogEntry),
     CurrentState(CurrentStateRelease),
 }
 
@@ -32,7 +43,12 @@ impl ReleaseSource {
     pub fn version_tag(&self) -> &str {
         match self {
             ReleaseSource::Github(src) => &src.tag_name,
+            ReleaseSource::Gitlab(src) => &src.tag_name,
+            ReleaseSource::Forgejo(src) => &src.tag_name,
             ReleaseSource::Axodotdev(src) => &src.tag_name,
+            ReleaseSource::Local(src) => &src.tag_name,
+            ReleaseSource::Git(src) => &src.tag_name,
+            ReleaseSource::Changelog(src) => &src.version,
             ReleaseSource::CurrentState(src) => src.version.as_deref().unwrap_or("current"),
         }
     }
@@ -41,7 +57,12 @@ impl ReleaseSource {
     pub fn is_prerelease(&self) -> bool {
         match self {
             ReleaseSource::Github(src) => src.prerelease,
+            ReleaseSource::Gitlab(src) => src.is_prerelease(),
+            ReleaseSource::Forgejo(src) => src.prerelease,
             ReleaseSource::Axodotdev(src) => src.prerelease,
+            ReleaseSource::Local(src) => src.prerelease,
+            ReleaseSource::Git(src) => src.prerelease,
+            ReleaseSource::Changelog(src) => src.prerelease,
             ReleaseSource::CurrentState(src) => src.prerelease,
         }
     }
@@ -50,16 +71,41 @@ impl ReleaseSource {
     pub fn date(&self) -> Option<&str> {
         match self {
             ReleaseSource::Github(src) => Some(src.published_at.as_str()),
+            ReleaseSource::Gitlab(src) => Some(src.date()),
+            ReleaseSource::Forgejo(src) => Some(src.date()),
             ReleaseSource::Axodotdev(src) => Some(src.created_at.as_str()),
+            ReleaseSource::Local(_src) => None,
+            ReleaseSource::Git(src) => src.date.as_deref(),
+            ReleaseSource::Changelog(src) => src.date.as_deref(),
             ReleaseSource::CurrentState(src) => src.date.as_deref(),
         }
     }
 
+    /// Get the date this was published as an actual date, if we can make sense of it
+    ///
+    /// Dates without a time (like the ones in changelog headings) are taken to be at midnight UTC.
+    pub fn parsed_date(&self) -> Option<DateTime<FixedOffset>> {
+        let date = self.date()?;
+        if let Ok(parsed_date) = DateTime::parse_from_rfc3339(date) {
+            return Some(parsed_date);
+        }
+        let parsed_date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+        Some(
+            parsed_date
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+                .fixed_offset(),
+        )
+    }
+
     /// Get a pretty formatted version of the date
     pub fn formatted_date(&self) -> Option<String> {
         self.date().map(|date| {
             if let Ok(parsed_date) = DateTime::parse_from_rfc3339(date) {
                 parsed_date.format("%b %e %Y at %R UTC").to_string()
+            } else if let Ok(parsed_date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                // Changelog headings only have a day
+                parsed_date.format("%b %e %Y").to_string()
             } else {
                 date.to_owned()
             }
@@ -70,7 +116,12 @@ impl ReleaseSource {
     pub fn name(&self) -> Option<&str> {
         match self {
             ReleaseSource::Github(src) => src.name.as_deref(),
+            ReleaseSource::Gitlab(src) => src.name.as_deref(),
+            ReleaseSource::Forgejo(src) => src.name.as_deref(),
             ReleaseSource::Axodotdev(src) => Some(src.name.as_str()),
+            ReleaseSource::Local(src) => src.name.as_deref(),
+            ReleaseSource::Git(_src) => None,
+            ReleaseSource::Changelog(_src) => None,
             ReleaseSource::CurrentState(_src) => None,
         }
     }
@@ -79,11 +130,26 @@ impl ReleaseSource {
     pub(crate) fn body(&self) -> Option<&str> {
         match self {
             ReleaseSource::Github(src) => src.body.as_deref(),
+            ReleaseSource::Gitlab(src) => src.description.as_deref(),
+            ReleaseSource::Forgejo(src) => src.body.as_deref(),
             ReleaseSource::Axodotdev(src) => Some(src.body.as_str()),
+            ReleaseSource::Local(src) => src.body.as_deref(),
+            ReleaseSource::Git(src) => src.body.as_deref(),
+            ReleaseSource::Changelog(src) => Some(src.notes.as_str()),
             ReleaseSource::CurrentState(_src) => None,
         }
     }
 
+    /// Whether this release has a real tag in the repository
+    ///
+    /// Current state releases and entries from the changelog file only have versions.
+    pub fn is_tagged(&self) -> bool {
+        !matches!(
+            self,
+            ReleaseSource::CurrentState(_) | ReleaseSource::Changelog(_)
+        )
+    }
+
     /// Find out if we're working with a current state release
     pub fn is_current_state(&self) -> bool {
         matches!(self, ReleaseSource::CurrentState(_))
@@ -102,21 +168,42 @@ pub struct Release {
 impl Release {
     pub async fn new(
         source: ReleaseSource,
-        repo: Option<&GithubRepo>,
         artifacts_config: Option<&ArtifactsConfig>,
+    ) -> Result<Self> {
+        Self::with_forge(source, artifacts_config, &ForgeConfig::default()).await
+    }
+
+    /// Like [`Release::new`][], but with the settings for reaching the forge the
+    /// release came from, so files can be fetched from private projects
+    pub async fn with_forge(
+        source: ReleaseSource,
+        artifacts_config: Option<&ArtifactsConfig>,
+        forge_config: &ForgeConfig,
     ) -> Result<Self> {
         // If artifacts are disabled then bail out, because all this code is just artifacts stuff
         let Some(artifacts_config) = artifacts_config else {
             return Ok(Self {
                 manifest: None,
                 source,
-                artifacts: ReleaseArtifacts::new(None),
+                artifacts: ReleaseArtifacts::new(vec![]),
             });
         };
 
-        let manifest = if let (ReleaseSource::Github(gh_release), Some(repo)) = (&source, repo) {
+        let manifest = if let ReleaseSource::Github(gh_release) = &source {
+            if artifacts_config.cargo_dist {
+                Self::fetch_manifest_github(gh_release, forge_config).await?
+            } else {
+                None
+            }
+        } else if let ReleaseSource::Gitlab(gitlab_release) = &source {
             if artifacts_config.cargo_dist {
-                Self::fetch_manifest_github(gh_release, repo).await?
+                Self::fetch_manifest_gitlab(gitlab_release, forge_config).await?
+            } else {
+                None
+            }
+        } else if let ReleaseSource::Forgejo(forgejo_release) = &source {
+            if artifacts_config.cargo_dist {
+                Self::fetch_manifest_forgejo(forgejo_release, forge_config).await?
             } else {
                 None
             }
@@ -126,28 +213,41 @@ impl Release {
             } else {
                 None
             }
+        } else if let ReleaseSource::Local(local_release) = &source {
+            if artifacts_config.cargo_dist {
+                local_release.manifest.clone()
+            } else {
+                None
+            }
         } else {
             // FIXME: warn if cargo-dist enabled?
             None
         };
 
-        // Compute the artifacts for this release
-        //
-        // In the future with multi-tenant oranda support, this None
-        // can be replaced with the name of the app we want to focus in on
-        let mut artifacts = ReleaseArtifacts::new(None);
+        // Compute the artifacts for this release, focusing on the apps this site is for
+        let mut artifacts = ReleaseArtifacts::new(artifacts_config.shown_apps());
 
         // Add data from various sources
         if let ReleaseSource::Github(gh_release) = &source {
             artifacts.add_github(gh_release);
         }
+        if let ReleaseSource::Gitlab(gitlab_release) = &source {
+            artifacts.add_gitlab(gitlab_release);
+        }
+        if let ReleaseSource::Forgejo(forgejo_release) = &source {
+            artifacts.add_forgejo(forgejo_release);
+        }
         if let ReleaseSource::Axodotdev(axo_release) = &source {
             artifacts.add_axodotdev(axo_release);
         }
+        if let ReleaseSource::Local(local_release) = &source {
+            artifacts.add_local(local_release);
+        }
         if let Some(manifest) = &manifest {
             artifacts.add_cargo_dist(manifest);
         }
         artifacts.add_package_managers(artifacts_config);
+        artifacts.add_signatures();
         artifacts.add_inference();
 
         // Compute the final result
@@ -167,48 +267,60 @@ impl Release {
 
     async fn fetch_manifest_github(
         gh_release: &GithubRelease,
-        repo: &GithubRepo,
+        forge_config: &ForgeConfig,
     ) -> Result<Option<DistManifest>> {
-        let mut encoded_tag = String::new();
-        url_escape::encode_component_to_string(&gh_release.tag_name, &mut encoded_tag);
-        if gh_release.has_dist_manifest() {
-            let request = octolotl::request::ReleaseAsset::new(
-                &repo.owner,
-                &repo.name,
-                &encoded_tag,
-                cargo_dist::MANIFEST_FILENAME,
-            );
-            let response = octolotl::Request::send(&request, true)
-                .await?
-                .error_for_status()?;
-
-            Ok(Self::parse_response(response, &gh_release.tag_name).await?)
-        } else {
-            Ok(None)
-        }
+        let Some(manifest) = gh_release
+            .fetch_asset(cargo_dist::MANIFEST_FILENAME, forge_config)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Self::parse_manifest(manifest, &gh_release.tag_name))
+    }
+
+    async fn fetch_manifest_gitlab(
+        gitlab_release: &GitlabRelease,
+        forge_config: &ForgeConfig,
+    ) -> Result<Option<DistManifest>> {
+        let Some(manifest) = gitlab_release
+            .fetch_asset(cargo_dist::MANIFEST_FILENAME, forge_config)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Self::parse_manifest(manifest, &gitlab_release.tag_name))
+    }
+
+    async fn fetch_manifest_forgejo(
+        forgejo_release: &ForgejoRelease,
+        forge_config: &ForgeConfig,
+    ) -> Result<Option<DistManifest>> {
+        let Some(manifest) = forgejo_release
+            .fetch_asset(cargo_dist::MANIFEST_FILENAME, forge_config)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Self::parse_manifest(manifest, &forgejo_release.tag_name))
     }
 
     async fn fetch_manifest_axodotdev(axo_release: &AxoRelease) -> Result<Option<DistManifest>> {
         let mut encoded_tag = String::new();
         url_escape::encode_component_to_string(&axo_release.tag_name, &mut encoded_tag);
         if axo_release.has_dist_manifest() {
-            let response = reqwest::get(axo_release.asset_url("dist-manifest.json").unwrap())
-                .await?
-                .error_for_status()?;
+            let response =
+                http_cache::get(axo_release.asset_url("dist-manifest.json").unwrap()).await?;
 
-            Ok(Self::parse_response(response, &axo_release.tag_name).await?)
+            Ok(Self::parse_manifest(response.text(), &axo_release.tag_name))
         } else {
             Ok(None)
         }
     }
 
-    async fn parse_response(
-        response: reqwest::Response,
-        tag: &str,
-    ) -> Result<Option<DistManifest>> {
-        let res = response.text().await?;
-        let src = SourceFile::new("dist-manifest.json", res);
-        Ok(match src.deserialize_json::<DistManifest>() {
+    /// Parse a dist-manifest.json, warning (and returning None) if we can't understand it
+    pub(crate) fn parse_manifest(contents: String, tag: &str) -> Option<DistManifest> {
+        let src = SourceFile::new("dist-manifest.json", contents);
+        match src.deserialize_json::<DistManifest>() {
             Ok(manifest) => Some(manifest),
             Err(e) => {
                 // Try partially parsing the manifest to get schema version info
@@ -237,6 +349,6 @@ impl Release {
                 }
                 None
             }
-        })
+        }
     }
 }
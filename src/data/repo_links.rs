@@ -0,0 +1,120 @@
+//! Links into a repository's web UI (issues, commits, comparisons between tags)
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::config::ReleasesSource;
+use crate::site::markdown::TextSpan;
+
+use super::split_repo_url;
+
+/// Builds links to things in a repository, following the conventions of the forge it's on
+#[derive(Debug, Clone)]
+pub struct RepoLinks {
+    /// The forge's URL, like `https://github.com`
+    host_url: String,
+    /// The repository's URL, like `https://github.com/axodotdev/oranda`
+    repo_url: String,
+    /// Whether this is on GitLab, which puts everything under `/-/`
+    gitlab: bool,
+}
+
+impl RepoLinks {
+    /// Get links for a repository URL, if we understand it
+    pub fn new(repo_url: &str, source: &ReleasesSource) -> Option<Self> {
+        let (host_url, path) = split_repo_url(repo_url).ok()?;
+        let gitlab = matches!(source, ReleasesSource::GitLab)
+            || host_url.split(['/', '.', ':']).any(|part| part == "gitlab");
+        Some(RepoLinks {
+            repo_url: format!("{host_url}/{path}"),
+            host_url,
+            gitlab,
+        })
+    }
+
+    /// The page comparing two tags
+    pub fn compare(&self, from_tag: &str, to_tag: &str) -> String {
+        self.repo_page(&format!("compare/{from_tag}...{to_tag}"))
+    }
+
+    /// The page for an issue (GitHub and Forgejo redirect this to pull requests too)
+    pub fn issue(&self, number: &str) -> String {
+        self.repo_page(&format!("issues/{number}"))
+    }
+
+    /// The page for a commit
+    pub fn commit(&self, sha: &str) -> String {
+        self.repo_page(&format!("commit/{sha}"))
+    }
+
+    /// The profile page of a user on the forge
+    pub fn user(&self, username: &str) -> String {
+        format!("{}/{username}", self.host_url)
+    }
+
+    /// Turn issue references (`#123`, `GH-123`), mentions (`@user`) and commit SHAs
+    /// in some text into links
+    pub fn linkify(&self, text: &str) -> Vec<TextSpan> {
+        static REFERENCE: OnceLock<Regex> = OnceLock::new();
+        let reference = REFERENCE.get_or_init(|| {
+            Regex::new(
+                r"(?:#|GH-)(?<issue>[0-9]+)|@(?<user>[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?)|(?<sha>[0-9a-f]{7,40})",
+            )
+            .expect("reference regex should be valid")
+        });
+
+        let mut spans = vec![];
+        let mut plain_start = 0;
+        for captures in reference.captures_iter(text) {
+            let whole = captures
+                .get(0)
+                .expect("regex match should have a whole match");
+            // References have to stand on their own (so not `a#1`, `me@example.com` or `cafe1234beef0`)
+            let is_word_char = |c: char| c.is_ascii_alphanumeric() || "_-/@#.".contains(c);
+            let before = text[..whole.start()].chars().next_back();
+            let after = text[whole.end()..].chars().next();
+            if before.is_some_and(is_word_char)
+                || after.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            let url = if let Some(issue) = captures.name("issue") {
+                self.issue(issue.as_str())
+            } else if let Some(user) = captures.name("user") {
+                self.user(user.as_str())
+            } else if let Some(sha) = captures.name("sha") {
+                // Plain words and numbers can be made of hex digits too
+                let sha = sha.as_str();
+                if !sha.chars().any(|c| c.is_ascii_digit())
+                    || !sha.chars().any(|c| c.is_ascii_alphabetic())
+                {
+                    continue;
+                }
+                self.commit(sha)
+            } else {
+                continue;
+            };
+            if plain_start < whole.start() {
+                spans.push(TextSpan::Text(text[plain_start..whole.start()].to_owned()));
+            }
+            spans.push(TextSpan::Link {
+                text: whole.as_str().to_owned(),
+                url,
+            });
+            plain_start = whole.end();
+        }
+        if plain_start < text.len() {
+            spans.push(TextSpan::Text(text[plain_start..].to_owned()));
+        }
+        spans
+    }
+
+    fn repo_page(&self, page: &str) -> String {
+        if self.gitlab {
+            format!("{}/-/{page}", self.repo_url)
+        } else {
+            format!("{}/{page}", self.repo_url)
+        }
+    }
+}
@@ -30,10 +30,6 @@ pub enum OrandaError {
     #[diagnostic(transparent)]
     AxoProject(#[from] axoproject::errors::AxoprojectError),
 
-    #[error(transparent)]
-    #[diagnostic(transparent)]
-    Octolotl(#[from] octolotl::OctolotlError),
-
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
 
@@ -62,9 +58,20 @@ pub enum OrandaError {
     InvalidOrandaCSSOverride { path: String },
 
     #[error("Failed fetching releases from Github.")]
+    #[diagnostic(help(
+        "if you're being rate limited, put a GitHub token in the {token_env} environment variable. If your repository isn't on GitHub (like a self-hosted GitLab at git.company.com), set components.source to \"gitlab\" or \"forgejo\", since we can only tell those apart by their host name"
+    ))]
     GithubReleasesFetchError {
+        token_env: String,
+        #[source]
+        details: reqwest::Error,
+    },
+
+    #[error("Failed fetching releases from {forge}.")]
+    ForgeReleasesFetchError {
+        forge: String,
         #[source]
-        details: octolotl::OctolotlError,
+        details: reqwest::Error,
     },
 
     #[error("Failed fetching releases from axo Releases.")]
@@ -89,6 +96,16 @@ pub enum OrandaError {
     #[error(transparent)]
     FSExtra(#[from] fs_extra::error::Error),
 
+    #[error("Couldn't find any local releases in {paths}")]
+    #[diagnostic(help(
+        "Did you build your release (e.g. with `cargo dist build`)? You can change where we look with components.local_releases.paths"
+    ))]
+    LocalReleasesNotFound { paths: String },
+
+    #[error("{url} isn't in the cache, and we're offline")]
+    #[diagnostic(help("build once without --offline to fill the cache"))]
+    OfflineCacheMiss { url: String },
+
     #[error("failed to read {filedesc} at {path}")]
     FileNotFound { filedesc: String, path: String },
 
@@ -169,6 +186,22 @@ pub enum OrandaError {
         details: axoproject::errors::AxoprojectError,
     },
 
+    #[error("Couldn't find a changelog file to read entries from")]
+    #[diagnostic(help(
+        "Add a CHANGELOG.md, or set components.changelog.path to point at your changelog file"
+    ))]
+    ChangelogFileMissing,
+
+    #[error("Unable to parse changelog file {path}")]
+    #[diagnostic(help(
+        "Make sure that each version in your changelog has a heading, like \"## 1.0.0 - 2023-04-05\""
+    ))]
+    ChangelogFileParseFailed {
+        path: Utf8PathBuf,
+        #[source]
+        details: parse_changelog::Error,
+    },
+
     #[error("Failed to loading funding details at {path}")]
     #[diagnostic(severity = "warn")]
     FundingLoadFailed {
@@ -191,6 +224,36 @@ pub enum OrandaError {
         details: toml_edit::TomlError,
     },
 
+    #[error("Item {index} of build.nav is invalid: {details}")]
+    #[diagnostic(help(
+        "Each item needs exactly one of `builtin`, `page` or `url`, and links to other sites need a `label`."
+    ))]
+    InvalidNavItem { index: usize, details: String },
+
+    #[error("The announcement's expiry date \"{date}\" isn't a date")]
+    #[diagnostic(help("marketing.announcement.expires needs to look like \"2024-05-01\"."))]
+    InvalidAnnouncementExpiry { date: String },
+
+    #[error("styles.custom_theme.{field} can't be \"{value}\"")]
+    #[diagnostic(help(
+        "Values are put into CSS as they are, so they can't be empty or contain `;`, `{{`, `}}`, `<` or `>`."
+    ))]
+    InvalidCustomTheme { field: String, value: String },
+
+    #[error("Failed to parse the data file {path}")]
+    #[diagnostic(help("Files in your data directory need to be valid JSON, YAML or TOML."))]
+    DataFileParseFailed {
+        path: Utf8PathBuf,
+        /// The file we were trying to parse
+        #[source_code]
+        file: axoasset::SourceFile,
+        /// Where the error is
+        #[label("{details}")]
+        span: Option<miette::SourceSpan>,
+        /// What's wrong
+        details: String,
+    },
+
     #[error("We were unable to watch your filesystem for changes")]
     #[diagnostic(help = "Make sure that oranda has privileges to set up file watchers!")]
     FilesystemWatchError(#[from] notify_debouncer_mini::notify::Error),
@@ -0,0 +1,370 @@
+//! A cache for everything we fetch over HTTP, kept on disk between builds
+//!
+//! Responses are reused for a while without asking again, and after that they're
+//! revalidated with their ETag (or Last-Modified date), which GitHub doesn't count
+//! against rate limits. In offline mode, the cache is all we've got.
+//!
+//! Responses are cached per URL *and* credentials, so something fetched with a private
+//! token is only ever reused by requests that send the same token.
+
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axoasset::{Asset, LocalAsset};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::header::{
+    AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::*;
+
+/// How long a response is used without checking whether it changed
+pub const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The cache every fetch goes through (no caching at all until [`HttpCache::set_global`][] is called)
+static GLOBAL_CACHE: RwLock<Option<HttpCache>> = RwLock::new(None);
+
+#[derive(Clone, Debug)]
+pub struct HttpCache {
+    /// The directory cached responses are kept in
+    pub dir: Utf8PathBuf,
+    /// How long a response is used without checking whether it changed
+    pub ttl: Duration,
+    /// Only use cached responses, and never touch the network
+    pub offline: bool,
+}
+
+/// A response body, either freshly fetched or from the cache
+#[derive(Clone, Debug)]
+pub struct Fetched {
+    pub body: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+/// What we remember about a cached response (its body is stored next to this)
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+    /// When we last got (or revalidated) this response, in seconds since the epoch
+    fetched_at: u64,
+}
+
+impl HttpCache {
+    pub fn new(dir: Utf8PathBuf, offline: bool) -> Self {
+        HttpCache {
+            dir,
+            ttl: DEFAULT_TTL,
+            offline,
+        }
+    }
+
+    /// Where the cache lives if ORANDA_CACHE_DIR doesn't say otherwise
+    /// (the user's cache directory, or the temp directory if they don't have one)
+    pub fn default_dir() -> Utf8PathBuf {
+        if let Ok(dir) = std::env::var("ORANDA_CACHE_DIR") {
+            return Utf8PathBuf::from(dir);
+        }
+        directories::ProjectDirs::from("dev", "axo", "oranda")
+            .and_then(|dirs| Utf8PathBuf::from_path_buf(dirs.cache_dir().to_owned()).ok())
+            .or_else(|| Utf8PathBuf::from_path_buf(std::env::temp_dir().join("oranda-cache")).ok())
+            .unwrap_or_else(|| Utf8PathBuf::from(".oranda-cache"))
+    }
+
+    /// Make every fetch in this process go through this cache
+    pub fn set_global(cache: Option<HttpCache>) {
+        *GLOBAL_CACHE
+            .write()
+            .expect("HTTP cache should not be poisoned") = cache;
+    }
+
+    /// Get the cache every fetch goes through, if there is one
+    pub fn global() -> Option<HttpCache> {
+        GLOBAL_CACHE
+            .read()
+            .expect("HTTP cache should not be poisoned")
+            .clone()
+    }
+
+    /// Whether we're only allowed to use cached responses
+    pub fn is_offline() -> bool {
+        Self::global().is_some_and(|cache| cache.offline)
+    }
+
+    /// Fetch a URL with a plain GET request, through this cache
+    pub async fn get(&self, url: &str) -> Result<Fetched> {
+        self.fetch(reqwest::Client::new().get(url), send_plain)
+            .await
+    }
+
+    /// Send a request through this cache
+    ///
+    /// `send` does the actual sending, so callers can add retries and their own errors.
+    /// It gets the request with any revalidation headers added, and shouldn't treat
+    /// a "304 Not Modified" response as an error.
+    pub async fn fetch<F, Fut>(&self, request: RequestBuilder, send: F) -> Result<Fetched>
+    where
+        F: FnOnce(RequestBuilder) -> Fut,
+        Fut: Future<Output = Result<Response>>,
+    {
+        let (url, credentials) = request_identity(&request);
+        let key = cache_key(&url, credentials.as_deref());
+        let entry_path = self.dir.join(format!("{key}.json"));
+        let body_path = self.dir.join(&key);
+        let cached = load_entry(&entry_path, &body_path);
+
+        if self.offline {
+            return match cached {
+                Some((entry, body)) => Ok(Fetched {
+                    body,
+                    content_type: entry.content_type,
+                }),
+                None => Err(OrandaError::OfflineCacheMiss { url }),
+            };
+        }
+
+        let now = now();
+        let mut request = request;
+        if let Some((entry, body)) = &cached {
+            if now.saturating_sub(entry.fetched_at) < self.ttl.as_secs() {
+                return Ok(Fetched {
+                    body: body.clone(),
+                    content_type: entry.content_type.clone(),
+                });
+            }
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match send(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                // If the server is down, something old is better than nothing at all
+                // (but if it said no, like to a revoked token, that's the answer)
+                let Some((entry, body)) = cached.filter(|_| is_unavailable(&e)) else {
+                    return Err(e);
+                };
+                tracing::warn!("Couldn't refresh {}, using a cached copy", entry.url);
+                return Ok(Fetched {
+                    body,
+                    content_type: entry.content_type,
+                });
+            }
+        };
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some((mut entry, body)) = cached {
+                entry.fetched_at = now;
+                store_entry(&self.dir, &entry_path, &entry, None);
+                return Ok(Fetched {
+                    body,
+                    content_type: entry.content_type,
+                });
+            }
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned())
+        };
+        let entry = CacheEntry {
+            url,
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            content_type: header(CONTENT_TYPE),
+            fetched_at: now,
+        };
+        let body = response.bytes().await?.to_vec();
+        store_entry(&self.dir, &entry_path, &entry, Some((&body_path, &body)));
+        Ok(Fetched {
+            body,
+            content_type: entry.content_type,
+        })
+    }
+}
+
+impl Fetched {
+    async fn from_response(response: Response) -> Result<Self> {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_owned());
+        let body = response.bytes().await?.to_vec();
+        Ok(Fetched { body, content_type })
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Send a request through the global cache (or straight to the network if there isn't one)
+///
+/// See [`HttpCache::fetch`][] for details.
+pub async fn fetch<F, Fut>(request: RequestBuilder, send: F) -> Result<Fetched>
+where
+    F: FnOnce(RequestBuilder) -> Fut,
+    Fut: Future<Output = Result<Response>>,
+{
+    match HttpCache::global() {
+        Some(cache) => cache.fetch(request, send).await,
+        None => Fetched::from_response(send(request).await?).await,
+    }
+}
+
+/// Fetch a URL with a plain GET request, through the global cache
+pub async fn get(url: &str) -> Result<Fetched> {
+    send(reqwest::Client::new().get(url)).await
+}
+
+/// Send a request (e.g. one with auth headers) through the global cache, with no special handling
+pub async fn send(request: RequestBuilder) -> Result<Fetched> {
+    fetch(request, send_plain).await
+}
+
+/// Load a file as a string, through the global cache if it's a URL
+pub async fn load_string(origin_path: &str) -> Result<String> {
+    if is_remote(origin_path) {
+        Ok(get(origin_path).await?.text())
+    } else {
+        Ok(Asset::load_string(origin_path).await?)
+    }
+}
+
+/// Copy a file into a directory, through the global cache if it's a URL
+///
+/// Remote files are named the same way axoasset would name them: after the URL's path,
+/// with an extension from their content type if the path doesn't have one.
+pub async fn copy(origin_path: &str, dest_dir: &str) -> Result<Utf8PathBuf> {
+    if !is_remote(origin_path) {
+        let dest_path = Asset::copy(origin_path, dest_dir).await?;
+        return Ok(Utf8PathBuf::from_path_buf(dest_path).unwrap_or_default());
+    }
+    let fetched = get(origin_path).await?;
+    let mut filename = url::Url::parse(origin_path)?
+        .path()
+        .trim_start_matches('/')
+        .replace('/', "_");
+    if !filename.contains('.') {
+        let extension = fetched
+            .content_type
+            .as_deref()
+            .and_then(|mimetype| mimetype.split(';').next())
+            .and_then(|mimetype| mimetype.split_once('/'))
+            .map(|(_, subtype)| match subtype {
+                "plain" => "txt",
+                "svg+xml" => "svg",
+                subtype => subtype.split('+').next().unwrap_or(subtype),
+            })
+            .unwrap_or("txt");
+        filename = format!("{filename}.{extension}");
+    }
+    let dest_path = Utf8Path::new(dest_dir).join(filename);
+    LocalAsset::new(dest_path.as_str(), fetched.body)?.write(dest_dir)?;
+    Ok(dest_path)
+}
+
+fn is_remote(origin_path: &str) -> bool {
+    origin_path.starts_with("http://") || origin_path.starts_with("https://")
+}
+
+async fn send_plain(request: RequestBuilder) -> Result<Response> {
+    Ok(request.send().await?.error_for_status()?)
+}
+
+/// The URL a request is for, and the credentials it sends (if any)
+fn request_identity(request: &RequestBuilder) -> (String, Option<String>) {
+    let Some(request) = request.try_clone().and_then(|request| request.build().ok()) else {
+        return (String::new(), None);
+    };
+    // GitHub and Forgejo use Authorization, GitLab uses PRIVATE-TOKEN
+    let credentials = [AUTHORIZATION.as_str(), "private-token"]
+        .into_iter()
+        .filter_map(|name| request.headers().get(name))
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+        .collect::<Vec<_>>();
+    let credentials = (!credentials.is_empty()).then(|| credentials.join("\n"));
+    (request.url().to_string(), credentials)
+}
+
+/// Where to keep a response, which is a hash so the credentials don't end up on disk
+fn cache_key(url: &str, credentials: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    if let Some(credentials) = credentials {
+        hasher.update(b"\n");
+        hasher.update(credentials.as_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut key, byte| {
+            let _ = write!(key, "{byte:02x}");
+            key
+        })
+}
+
+/// Whether an error means the server couldn't be reached or had a problem of its own
+/// (as opposed to it answering, but not with what we wanted)
+fn is_unavailable(error: &OrandaError) -> bool {
+    let details = match error {
+        OrandaError::Reqwest(details)
+        | OrandaError::GithubReleasesFetchError { details, .. }
+        | OrandaError::ForgeReleasesFetchError { details, .. } => details,
+        _ => return false,
+    };
+    details.is_connect()
+        || details.is_timeout()
+        || details
+            .status()
+            .is_some_and(|status| status.is_server_error())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn load_entry(entry_path: &Utf8Path, body_path: &Utf8Path) -> Option<(CacheEntry, Vec<u8>)> {
+    let entry = std::fs::read_to_string(entry_path).ok()?;
+    let entry = serde_json::from_str(&entry).ok()?;
+    let body = std::fs::read(body_path).ok()?;
+    Some((entry, body))
+}
+
+/// Save a response to the cache (and its body, if it's new)
+///
+/// Failing to do this isn't worth failing a build over, we'll just fetch it again next time.
+fn store_entry(
+    dir: &Utf8Path,
+    entry_path: &Utf8Path,
+    entry: &CacheEntry,
+    body: Option<(&Utf8Path, &[u8])>,
+) {
+    let result = (|| -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        if let Some((body_path, body)) = body {
+            std::fs::write(body_path, body)?;
+        }
+        std::fs::write(entry_path, serde_json::to_string(entry)?)
+    })();
+    if let Err(e) = result {
+        tracing::debug!("Couldn't write {} to the HTTP cache: {e}", entry.url);
+    }
+}
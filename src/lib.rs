@@ -6,5 +6,6 @@ pub mod data;
 pub mod errors;
 pub mod formatter;
 pub mod generate;
+pub mod http_cache;
 pub mod paths;
 pub mod site;
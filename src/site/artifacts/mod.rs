@@ -1,10 +1,12 @@
 use axoasset::LocalAsset;
 use axoproject::platforms::triple_to_display_name;
 use camino::Utf8PathBuf;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::config::Config;
-use crate::data::artifacts::{File, FileIdx, InstallMethod, InstallerIdx, TargetTriple};
+use crate::config::{repo_host, ArtifactSystem, Config};
+use crate::data::artifacts::{
+    File, FileIdx, InstallMethod, InstallerIdx, SignatureScheme, Targ, TargetTriple,
+};
 use crate::data::{Context, Release};
 use crate::errors::*;
 
@@ -23,7 +25,13 @@ const JSON_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Serialize, Debug, Clone)]
 pub struct Platform {
+    /// Unique id for this entry in the platform dropdown
+    ///
+    /// This is the target triple, with the system appended for distro-specific entries
+    id: String,
     target: Vec<TargetTriple>,
+    /// The Linux distro this entry is specialized for, if any
+    system: Option<ArtifactSystem>,
     display_name: String,
     installers: Vec<InstallerIdx>,
 }
@@ -44,23 +52,56 @@ pub struct ArtifactsContext {
     release: Release,
     os_script: String,
     has_checksum_files: bool,
+    has_signatures: bool,
+    verification_hints: Vec<VerificationHint>,
+    /// Archives of the source code, for the "build from source" section
+    source_files: Vec<(FileIdx, File)>,
+    /// Debug symbols and other files only developers will want
+    developer_files: Vec<(FileIdx, File)>,
+}
+
+/// Instructions for verifying downloads with a particular scheme
+#[derive(Serialize, Debug, Clone)]
+pub struct VerificationHint {
+    /// The scheme ("gpg", "minisign", "cosign", "github-attestation")
+    scheme: String,
+    /// Human-readable name of the scheme
+    label: String,
+    /// An example command for verifying one of the downloads
+    hint: String,
 }
 
 pub fn template_context(context: &Context, config: &Config) -> Result<Option<ArtifactsContext>> {
     let Some(release) = context.latest() else {
         return Ok(None);
     };
+    let release_context = release_context(context, release, config)?;
+    if release_context.downloadable_files.is_empty() {
+        tracing::warn!("You seem to have release automation set up, but we didn't detect any releases. The install page and associated widget will be empty. To disable this, set `artifacts: false`");
+    }
+    Ok(Some(release_context))
+}
+
+/// Make the artifacts context for any release (not just the latest one)
+pub fn release_context(
+    context: &Context,
+    release: &Release,
+    config: &Config,
+) -> Result<ArtifactsContext> {
     let os_script = javascript::build_os_script_path(&config.build.path_prefix);
-    let platforms_with_downloads = filter_platforms(release)
-        .into_iter()
+    let platforms = filter_platforms(release);
+    let mut platforms_with_downloads = platforms
+        .iter()
         .map(|(target, installers)| Platform {
-            display_name: triple_to_display_name(&target)
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| target.clone()),
-            target: vec![target],
-            installers,
+            id: target.clone(),
+            display_name: target_display_name(target),
+            target: vec![target.clone()],
+            system: None,
+            installers: installers.clone(),
         })
         .collect::<Vec<_>>();
+    // These go last so that OS autodetection picks the generic entry by default
+    platforms_with_downloads.extend(distro_platforms(release, &platforms));
 
     let mut downloadable_files: Vec<_> = release
         .artifacts
@@ -75,25 +116,32 @@ pub fn template_context(context: &Context, config: &Config) -> Result<Option<Art
                 installer
                     .targets
                     .keys()
-                    .map(|s| {
-                        triple_to_display_name(s)
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|| s.clone())
-                    })
+                    .map(|s| target_display_name(s))
                     .collect::<Vec<_>>(),
             ))
         })
         .collect();
     downloadable_files.sort_by_key(|(_, f, _)| f.name.clone());
 
-    if downloadable_files.is_empty() {
-        tracing::warn!("You seem to have release automation set up, but we didn't detect any releases. The install page and associated widget will be empty. To disable this, set `artifacts: false`");
-    }
     let has_checksum_files = downloadable_files
         .iter()
         .any(|(_, f, _)| f.checksum_file.is_some());
+    let has_signatures = downloadable_files
+        .iter()
+        .any(|(_, f, _)| f.signature.is_some());
+    let verification_hints = verification_hints(context, config, release, &downloadable_files);
+    let source_files = release
+        .artifacts
+        .source_tarballs()
+        .map(|(idx, file)| (idx, file.clone()))
+        .collect();
+    let developer_files = release
+        .artifacts
+        .developer_files()
+        .map(|(idx, file)| (idx, file.clone()))
+        .collect();
 
-    Ok(Some(ArtifactsContext {
+    Ok(ArtifactsContext {
         tag: release.source.version_tag().to_string(),
         formatted_date: release.source.formatted_date(),
         platforms_with_downloads,
@@ -101,7 +149,95 @@ pub fn template_context(context: &Context, config: &Config) -> Result<Option<Art
         downloadable_files,
         os_script,
         has_checksum_files,
-    }))
+        has_signatures,
+        verification_hints,
+        source_files,
+        developer_files,
+    })
+}
+
+/// The certificate identity and OIDC issuer to pass to `cosign verify-blob`
+///
+/// Keyless signatures made in CI are tied to the repository's URL, so we build the
+/// identity from that. If we don't know the repository, or can't tell which CI
+/// signed it, we leave placeholders in for the user to fill in.
+fn cosign_identity(config: &Config) -> (String, String) {
+    let repo_url = config.project.repository.as_deref().and_then(|url| {
+        let url = url.strip_prefix("git+").unwrap_or(url);
+        let url = url.trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+        url.starts_with("https://").then_some(url)
+    });
+    let Some(repo_url) = repo_url else {
+        return ("<identity>".to_owned(), "<issuer>".to_owned());
+    };
+    let issuer = match repo_host(repo_url).as_deref() {
+        Some("github.com") => "https://token.actions.githubusercontent.com",
+        Some("gitlab.com") => "https://gitlab.com",
+        _ => "<issuer>",
+    };
+    (format!("'^{repo_url}/'"), issuer.to_owned())
+}
+
+/// Make one example of how to verify a download for every way the downloads are signed
+fn verification_hints(
+    context: &Context,
+    config: &Config,
+    release: &Release,
+    downloadable_files: &DownloadableFiles,
+) -> Vec<VerificationHint> {
+    let mut hints = vec![];
+    let mut seen = BTreeSet::new();
+    for (_, file, _) in downloadable_files {
+        let Some(signature) = file.signature else {
+            continue;
+        };
+        if !seen.insert(signature.scheme) {
+            continue;
+        }
+        let sig_name = &release.artifacts.file(signature.file).name;
+        let name = &file.name;
+        let (scheme, label, hint) = match signature.scheme {
+            SignatureScheme::Gpg => ("gpg", "GPG", format!("gpg --verify {sig_name} {name}")),
+            SignatureScheme::Minisign => (
+                "minisign",
+                "minisign",
+                format!("minisign -Vm {name} -x {sig_name} -P <public key>"),
+            ),
+            SignatureScheme::Cosign => {
+                let (identity, issuer) = cosign_identity(config);
+                (
+                    "cosign",
+                    "cosign",
+                    format!("cosign verify-blob {name} --bundle {sig_name} --certificate-identity-regexp {identity} --certificate-oidc-issuer {issuer}"),
+                )
+            }
+        };
+        hints.push(VerificationHint {
+            scheme: scheme.to_owned(),
+            label: label.to_owned(),
+            hint,
+        });
+    }
+
+    let attestations = config
+        .components
+        .artifacts
+        .as_ref()
+        .is_some_and(|a| a.github_attestations);
+    if let (true, Some(repo), Some((_, file, _))) =
+        (attestations, &context.repo, downloadable_files.first())
+    {
+        hints.push(VerificationHint {
+            scheme: "github-attestation".to_owned(),
+            label: "GitHub attestation".to_owned(),
+            hint: format!(
+                "gh attestation verify {} --repo {}/{}",
+                file.name, repo.owner, repo.name
+            ),
+        });
+    }
+    hints
 }
 
 /// Write a JSON file containing a representation of the template context, for external integration
@@ -177,6 +313,54 @@ pub fn filter_platforms(release: &Release) -> Platforms {
     Platforms::default()
 }
 
+/// Make extra platform entries for every Linux distro that has a native installer
+///
+/// These have the same installers as the generic entry for the target, but with
+/// the ones native to that distro moved to the front.
+fn distro_platforms(release: &Release, platforms: &Platforms) -> Vec<Platform> {
+    let mut distro_platforms = vec![];
+    for (target, installers) in platforms {
+        let mut systems = BTreeSet::new();
+        for &idx in installers {
+            systems.extend(release.artifacts.installer(idx).systems.iter().copied());
+        }
+        for system in systems {
+            let (mut native, others): (Vec<_>, Vec<_>) = installers
+                .iter()
+                .partition(|&&idx| release.artifacts.installer(idx).systems.contains(&system));
+            native.extend(others);
+            distro_platforms.push(Platform {
+                id: format!("{target}-{}", system.id()),
+                display_name: format!(
+                    "{} ({})",
+                    target_display_name(target),
+                    system.display_name()
+                ),
+                target: vec![target.clone()],
+                system: Some(system),
+                installers: native,
+            });
+        }
+    }
+    distro_platforms
+}
+
+/// Get a human-readable name for a target, falling back to the triple itself
+fn target_display_name(target: &Targ) -> String {
+    let name = match target {
+        "aarch64-linux-android" => Some("Android arm64"),
+        "armv7-linux-androideabi" => Some("Android arm"),
+        "i686-linux-android" => Some("Android x86"),
+        "x86_64-linux-android" => Some("Android x64"),
+        "aarch64-apple-ios" => Some("iOS arm64"),
+        "powerpc-apple-darwin" => Some("macOS PowerPC"),
+        "x86_64-unknown-freebsd" => Some("FreeBSD x64"),
+        _ => triple_to_display_name(target),
+    };
+    name.map(|s| s.to_string())
+        .unwrap_or_else(|| target.to_owned())
+}
+
 /// Check if a target belongs to the "big four" sets of targets:
 /// - Linux x64
 /// - macOS ARM
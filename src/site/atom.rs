@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use atom_syndication::extension::ExtensionBuilder;
+use atom_syndication::{
+    CategoryBuilder, ContentBuilder, EntryBuilder, Feed, FeedBuilder, FixedDateTime, LinkBuilder,
+    PersonBuilder, Text,
+};
+
+use crate::config::Config;
+use crate::errors::Result;
+use crate::site::changelog::{feed_releases, feed_updated, ChangelogContext, FEED_NAMESPACE};
+use crate::site::link::generate_absolute;
+
+pub fn generate_atom_feed(context: &ChangelogContext, config: &Config) -> Result<Feed> {
+    let category = CategoryBuilder::default()
+        .term(format!("{} Changelog", &config.project.name))
+        .build();
+    let project_author = PersonBuilder::default()
+        .name(config.project.name.clone())
+        .uri(config.project.repository.clone())
+        .build();
+
+    let releases = feed_releases(context, config);
+    // Atom requires dates, so releases we don't have one for get the feed's,
+    // and if we don't know when any release came out, the feed was updated just now
+    let updated: FixedDateTime =
+        feed_updated(releases).unwrap_or_else(|| chrono::Utc::now().fixed_offset());
+    let mut entries = Vec::new();
+    for release in releases {
+        let link =
+            generate_absolute(config, &format!("changelog/{}", release.version_tag)).unwrap();
+        let authors = release
+            .contributors
+            .iter()
+            .map(|name| PersonBuilder::default().name(name.clone()).build())
+            .collect::<Vec<_>>();
+        let mut categories = vec![category.clone()];
+        categories.extend(release.category_counts.iter().map(|(category, count)| {
+            CategoryBuilder::default()
+                .term(category.heading().to_lowercase())
+                .label(Some(format!("{} ({count})", category.heading())))
+                .build()
+        }));
+        let entry = EntryBuilder::default()
+            .title(release.name.clone().unwrap_or(release.version_tag.clone()))
+            .id(link.clone())
+            .updated(release.date.unwrap_or(updated))
+            .published(release.date)
+            .authors(authors)
+            .categories(categories)
+            .summary(release.summary.clone().map(Text::plain))
+            .links(vec![LinkBuilder::default()
+                .href(link)
+                .rel("alternate".to_owned())
+                .build()])
+            .content(Some(
+                ContentBuilder::default()
+                    .value(Some(release.body.clone()))
+                    .content_type(Some("html".to_owned()))
+                    .build(),
+            ))
+            .build();
+        entries.push(entry);
+    }
+
+    let (prefix, namespace) = FEED_NAMESPACE;
+    let page_count = ExtensionBuilder::default()
+        .name(format!("{prefix}:pageCount"))
+        .value(Some(context.page_count.to_string()))
+        .build();
+    let extensions = BTreeMap::from([(
+        prefix.to_owned(),
+        BTreeMap::from([("pageCount".to_owned(), vec![page_count])]),
+    )]);
+    let feed = FeedBuilder::default()
+        .namespaces(BTreeMap::from([(prefix.to_owned(), namespace.to_owned())]))
+        .extensions(extensions)
+        .title(format!("{} Changelog", &config.project.name))
+        .subtitle(Some(Text::plain(format!(
+            "Changelog information for {}",
+            &config.project.name
+        ))))
+        .id(generate_absolute(config, "changelog").unwrap())
+        .updated(updated)
+        .authors(vec![project_author])
+        .categories(vec![category])
+        .links(vec![
+            LinkBuilder::default()
+                .href(generate_absolute(config, "changelog.atom").unwrap())
+                .rel("self".to_owned())
+                .build(),
+            LinkBuilder::default()
+                .href(generate_absolute(config, "changelog").unwrap())
+                .rel("alternate".to_owned())
+                .build(),
+        ])
+        .entries(entries)
+        .build();
+    Ok(feed)
+}
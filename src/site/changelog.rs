@@ -1,28 +1,94 @@
+use axoproject::changelog::changelog_for_version;
+use axoproject::errors::AxoprojectError;
 use axoproject::{Version, WorkspaceInfo, WorkspaceKind};
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, FixedOffset};
+use indexmap::IndexMap;
 use serde::Serialize;
 
-use crate::config::Config;
-use crate::data::{Context, Release};
+use crate::config::{ChangelogConfig, Config};
+use crate::data::changelog::{ChangelogCategory, ChangelogEntry, ChangelogSections};
+use crate::data::git;
+use crate::data::repo_links::RepoLinks;
+use crate::data::{parse_version as parse_tag, strip_tag_pattern, Context, Release, ReleaseSource};
 use crate::errors::*;
-use crate::site::{javascript, markdown};
+use crate::site::{javascript, link, markdown};
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct ChangelogContext {
     pub releases: Vec<ChangelogRelease>,
     pub has_prereleases: bool,
     pub has_rss_feed: bool,
     pub os_script: String,
+    /// Which page of the changelog this is (starting at 1)
+    pub current_page: usize,
+    /// How many pages the changelog is split into
+    pub page_count: usize,
+    /// Link to the page with newer releases
+    pub previous_page_url: Option<String>,
+    /// Link to the page with older releases
+    pub next_page_url: Option<String>,
+    /// The side nav, grouped by version line (empty if the nav isn't grouped)
+    pub nav_groups: Vec<ChangelogNavGroup>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct ChangelogRelease {
     pub is_prerelease: bool,
     pub version_tag: String,
     pub name: Option<String>,
     pub formatted_date: Option<String>,
+    /// When this was published, if we know
+    pub date: Option<DateTime<FixedOffset>>,
     pub body: String,
+    /// The release before this one, if there is one
+    pub previous_version_tag: Option<String>,
+    /// A link to the changes between the previous release and this one
+    pub compare_url: Option<String>,
+    /// Everyone who made commits for this release
+    pub contributors: Vec<String>,
+    /// The part of the body before any Keep a Changelog sections
+    pub intro: String,
+    /// The body's Keep a Changelog sections (empty if it doesn't have any)
+    pub sections: Vec<ChangelogReleaseSection>,
+    /// How many entries there are in each category of section
+    pub category_counts: IndexMap<ChangelogCategory, usize>,
+    /// A short summary of the category counts, like "2 added, 1 fixed"
+    pub summary: Option<String>,
+    /// Whether the release has a Security section
+    pub is_security: bool,
 }
 
+/// A Keep a Changelog section of a release's notes
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangelogReleaseSection {
+    pub category: ChangelogCategory,
+    pub title: String,
+    /// The id of the section, for linking to it
+    pub anchor: String,
+    pub body: String,
+    /// How many entries (list items) are in the section
+    pub entries: usize,
+}
+
+/// Releases from one version line (like "1.2") in the side nav
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangelogNavGroup {
+    pub name: String,
+    pub releases: Vec<ChangelogNavRelease>,
+    /// Whether all of these releases are prereleases (and so hidden by default)
+    pub is_prerelease: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangelogNavRelease {
+    pub version_tag: String,
+    pub is_prerelease: bool,
+}
+
+/// Make the context for the whole changelog, with every release on one page
+///
+/// See [`paginate`][] for splitting it up.
 pub fn index_context(
     context: &Context,
     config: &Config,
@@ -30,94 +96,354 @@ pub fn index_context(
 ) -> Result<ChangelogContext> {
     // Render an empty page if we're probably dealing with the "dummy" release generated as a
     // fallback.
-    if context.releases.len() == 1 && context.releases[0].source.is_current_state() {
-        return Ok(ChangelogContext {
-            releases: Vec::new(),
-            has_prereleases: false,
-            has_rss_feed: config
-                .components
-                .changelog
-                .clone()
-                .is_some_and(|c| c.rss_feed),
-            os_script: javascript::build_os_script_path(&config.build.path_prefix),
-        });
-    }
-    let releases = context
-        .releases
-        .iter()
-        .map(|release| single_context(release, config, project))
-        .collect();
+    let releases = if context.releases.len() == 1 && context.releases[0].source.is_current_state() {
+        Vec::new()
+    } else {
+        context
+            .releases
+            .iter()
+            .enumerate()
+            .map(|(idx, release)| {
+                single_context(release, context.releases.get(idx + 1), config, project)
+            })
+            .collect()
+    };
+    // The feeds have every release, but mention how many pages the site splits them into
+    let page_count = releases
+        .len()
+        .div_ceil(per_page(releases.len(), config))
+        .max(1);
     Ok(ChangelogContext {
+        has_prereleases: releases.iter().any(|r| r.is_prerelease),
         releases,
-        has_prereleases: context.has_prereleases,
         has_rss_feed: config
             .components
             .changelog
             .clone()
             .is_some_and(|c| c.rss_feed),
         os_script: javascript::build_os_script_path(&config.build.path_prefix),
+        current_page: 1,
+        page_count,
+        previous_page_url: None,
+        next_page_url: None,
+        nav_groups: Vec::new(),
     })
 }
 
+/// Split the changelog into pages of `components.changelog.per_page` releases
+///
+/// The first page is the changelog index, and the others are at `changelog/page/{n}/`.
+/// There's always at least one page, even if there aren't any releases.
+pub fn paginate(index: &ChangelogContext, config: &Config) -> Vec<ChangelogContext> {
+    let changelog_config = config.components.changelog.as_ref();
+    let per_page = per_page(index.releases.len(), config);
+    let group_nav = changelog_config.is_some_and(|c| c.group_nav);
+    let tag_pattern = config
+        .components
+        .artifacts
+        .as_ref()
+        .and_then(|a| a.tag_pattern.as_deref());
+
+    let chunks = if index.releases.is_empty() {
+        vec![&index.releases[..]]
+    } else {
+        index.releases.chunks(per_page).collect()
+    };
+    let page_count = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(idx, releases)| {
+            let current_page = idx + 1;
+            ChangelogContext {
+                releases: releases.to_vec(),
+                has_prereleases: releases.iter().any(|r| r.is_prerelease),
+                has_rss_feed: index.has_rss_feed,
+                os_script: index.os_script.clone(),
+                current_page,
+                page_count,
+                previous_page_url: (current_page > 1)
+                    .then(|| page_url(&config.build.path_prefix, current_page - 1)),
+                next_page_url: (current_page < page_count)
+                    .then(|| page_url(&config.build.path_prefix, current_page + 1)),
+                nav_groups: if group_nav {
+                    nav_groups(releases, tag_pattern)
+                } else {
+                    Vec::new()
+                },
+            }
+        })
+        .collect()
+}
+
+/// The XML namespace for the elements oranda adds to the RSS and Atom feeds
+///
+/// For now that's just `<oranda:pageCount>`, how many pages the changelog on the site is split into.
+pub const FEED_NAMESPACE: (&str, &str) = ("oranda", "https://opensource.axo.dev/oranda/");
+
+/// How many releases go on each page of the changelog (all of them, if it isn't paginated)
+fn per_page(release_count: usize, config: &Config) -> usize {
+    config
+        .components
+        .changelog
+        .as_ref()
+        .and_then(|c| c.per_page)
+        .filter(|per_page| *per_page > 0)
+        .unwrap_or(release_count.max(1))
+}
+
+/// The releases that go in feeds (the newest `components.changelog.feed_limit` of them)
+pub fn feed_releases<'a>(context: &'a ChangelogContext, config: &Config) -> &'a [ChangelogRelease] {
+    let limit = config
+        .components
+        .changelog
+        .as_ref()
+        .and_then(|c| c.feed_limit)
+        .unwrap_or(context.releases.len())
+        .min(context.releases.len());
+    &context.releases[..limit]
+}
+
+/// When the newest of some releases was published, for the "last updated" date of a feed
+pub fn feed_updated(releases: &[ChangelogRelease]) -> Option<DateTime<FixedOffset>> {
+    releases.iter().filter_map(|release| release.date).max()
+}
+
+/// The filename of a page of the changelog index
+pub fn page_filename(page: usize) -> String {
+    if page <= 1 {
+        "changelog.html".to_owned()
+    } else {
+        format!("changelog/page/{page}.html")
+    }
+}
+
+fn page_url(path_prefix: &Option<String>, page: usize) -> String {
+    if page <= 1 {
+        link::generate_relative(path_prefix, "changelog/")
+    } else {
+        link::generate_relative(path_prefix, &format!("changelog/page/{page}/"))
+    }
+}
+
+/// Group releases by their major and minor version (so "1.2.0" and "1.2.1" are both in "1.2"),
+/// keeping them in order
+fn nav_groups(releases: &[ChangelogRelease], tag_pattern: Option<&str>) -> Vec<ChangelogNavGroup> {
+    let mut groups: Vec<ChangelogNavGroup> = Vec::new();
+    for release in releases {
+        let version = match tag_pattern {
+            Some(pattern) => strip_tag_pattern(&release.version_tag, pattern).and_then(parse_tag),
+            None => parse_tag(&release.version_tag),
+        };
+        let name = version
+            .map(|v| format!("{}.{}", v.major, v.minor))
+            .unwrap_or_else(|| "Other".to_owned());
+        let nav_release = ChangelogNavRelease {
+            version_tag: release.version_tag.clone(),
+            is_prerelease: release.is_prerelease,
+        };
+        match groups.iter_mut().find(|group| group.name == name) {
+            Some(group) => {
+                group.is_prerelease &= release.is_prerelease;
+                group.releases.push(nav_release);
+            }
+            None => groups.push(ChangelogNavGroup {
+                name,
+                releases: vec![nav_release],
+                is_prerelease: release.is_prerelease,
+            }),
+        }
+    }
+    groups
+}
+
+/// Make the context for one release
+///
+/// `previous` is the release that came before it (the next one in [`Context::releases`][]).
 pub fn single_context(
     release: &Release,
+    previous: Option<&Release>,
     config: &Config,
     project: Option<&WorkspaceInfo>,
 ) -> ChangelogRelease {
+    let changelog_config = config.components.changelog.as_ref();
+    let repo_links = repo_links(config);
+    let version_tag = release.source.version_tag();
+    // Only releases with real tags can be compared, or looked up in the git history
+    let previous_tag = previous
+        .filter(|_| release.source.is_tagged())
+        .filter(|previous| previous.source.is_tagged())
+        .map(|previous| previous.source.version_tag());
+    let compare_url = previous_tag
+        .filter(|_| changelog_config.is_some_and(|c| c.compare_links))
+        .and_then(|previous_tag| {
+            repo_links
+                .as_ref()
+                .map(|links| links.compare(previous_tag, version_tag))
+        });
+    let contributors =
+        if release.source.is_tagged() && changelog_config.is_some_and(|c| c.contributors) {
+            git::contributors(Utf8Path::new("."), previous_tag, version_tag)
+        } else {
+            vec![]
+        };
+
+    let notes = release_notes(project, release, config).unwrap_or_else(|err| {
+        let msg = format!("Couldn't read the release notes for {version_tag}: {err}");
+        tracing::warn!("{}", &msg);
+        String::new()
+    });
+    let render =
+        |notes: &str| render_notes(notes, config, repo_links.as_ref()).unwrap_or("".to_string());
+    let sections = changelog_config
+        .filter(|c| c.categories)
+        .and_then(|_| ChangelogSections::parse(&notes))
+        .unwrap_or_default();
+    let mut category_counts = IndexMap::new();
+    for section in &sections.sections {
+        *category_counts.entry(section.category).or_insert(0) += section.entries;
+    }
+    let summary = (!category_counts.is_empty()).then(|| {
+        category_counts
+            .iter()
+            .map(|(category, count)| format!("{count} {}", category.heading().to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+
     ChangelogRelease {
         is_prerelease: release.source.is_prerelease(),
-        version_tag: release.source.version_tag().to_string(),
+        version_tag: version_tag.to_string(),
         name: release.source.name().map(|s| s.to_string()),
         formatted_date: release.source.formatted_date(),
-        body: build_release_body(project, release, config).unwrap_or("".to_string()),
+        date: release.source.parsed_date(),
+        body: render(&notes),
+        previous_version_tag: previous_tag.map(|tag| tag.to_owned()),
+        compare_url,
+        contributors,
+        intro: render(&sections.intro),
+        is_security: sections.has_security(),
+        sections: sections
+            .sections
+            .iter()
+            .map(|section| ChangelogReleaseSection {
+                category: section.category,
+                title: section.title.clone(),
+                anchor: section_anchor(version_tag, &section.title),
+                body: render(&section.notes),
+                entries: section.entries,
+            })
+            .collect(),
+        category_counts,
+        summary,
     }
 }
 
-// Unwrap that we can't avoid without adding an extra if let block, since if let chains aren't stable
-#[allow(clippy::unnecessary_unwrap)]
-fn build_release_body(
+/// The id of a section of a release, like `tag-1.0.0-fixed`
+fn section_anchor(version_tag: &str, title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    format!("tag-{version_tag}-{slug}")
+}
+
+/// Links into the project's repository, if it has one we understand
+fn repo_links(config: &Config) -> Option<RepoLinks> {
+    let repo_url = config.project.repository.as_deref()?;
+    RepoLinks::new(repo_url, &config.components.releases_source(Some(repo_url)))
+}
+
+/// Find the changelog file that release notes are read from
+///
+/// A configured `path` wins, then the changelog we found for the project (if any),
+/// then any of the usual changelog filenames in the current directory.
+pub fn changelog_file_path(
+    changelog_config: &ChangelogConfig,
+    project: Option<&WorkspaceInfo>,
+) -> Option<Utf8PathBuf> {
+    if changelog_config.path.is_none() {
+        if let Some(path) = project.and_then(|p| p.root_auto_includes.changelog.clone()) {
+            return Some(path);
+        }
+    }
+    ChangelogEntry::find_file(Utf8Path::new("."), changelog_config.path.as_deref())
+}
+
+/// The release notes for a release (as markdown)
+///
+/// These come from the changelog file if there is one (and we're reading it) and it
+/// has an entry for this version, otherwise from the release itself.
+fn release_notes(
     project: Option<&WorkspaceInfo>,
     release: &Release,
     config: &Config,
 ) -> Result<String> {
-    let contents = if config
+    let changelog_path = match &release.source {
+        // Entries made from the changelog file already have their notes
+        ReleaseSource::Changelog(_) => None,
+        _ => config
+            .components
+            .changelog
+            .as_ref()
+            .filter(|c| c.read_changelog_file)
+            .and_then(|c| changelog_file_path(c, project)),
+    };
+    if let Some(changelog_path) = changelog_path {
+        let tag = release.source.version_tag();
+        // Tags like `myapp-v1.2.0` only look like versions without the pattern's prefix
+        let version = config
+            .components
+            .artifacts
+            .as_ref()
+            .and_then(|a| a.tag_pattern.as_deref())
+            .and_then(|pattern| strip_tag_pattern(tag, pattern))
+            .unwrap_or(tag);
+        match changelog_for_version(&changelog_path, &parse_version(version, project)?) {
+            Ok(Some(changelog)) => return Ok(changelog.body),
+            Ok(None) | Err(AxoprojectError::ChangelogVersionNotFound { .. }) => {}
+            Err(e) => {
+                return Err(OrandaError::ChangelogParseFailed {
+                    name: config.project.name.clone(),
+                    version: tag.to_owned(),
+                    details: e,
+                })
+            }
+        }
+    }
+    // The changelog doesn't mention this release, so use the release's own notes
+    Ok(release.source.body().unwrap_or_default().to_owned())
+}
+
+fn render_notes(notes: &str, config: &Config, repo_links: Option<&RepoLinks>) -> Result<String> {
+    let autolinks = config
         .components
         .changelog
         .as_ref()
-        .is_some_and(|c| c.read_changelog_file)
-        && project.is_some()
-    {
-        let project = project.unwrap();
-        let version = release.source.version_tag();
-        let changelog = project
-            .changelog_for_version(&parse_version(version, project)?)
-            .map_err(|e| OrandaError::ChangelogParseFailed {
-                name: config.project.name.clone(),
-                version: version.to_owned(),
-                details: e,
-            })?;
-        if let Some(changelog) = changelog {
-            changelog.body
-        } else {
-            "".to_owned()
-        }
-    } else {
-        release.source.body().unwrap_or_default().to_owned()
-    };
-
-    markdown::to_html(&contents, &config.styles.syntax_theme)
+        .is_some_and(|c| c.autolinks);
+    match repo_links.filter(|_| autolinks) {
+        Some(links) => markdown::to_html_with_links(notes, &config.styles.syntax_theme, |text| {
+            links.linkify(text)
+        }),
+        None => markdown::to_html(notes, &config.styles.syntax_theme),
+    }
 }
 
 /// Parses a version string into an axoproject-compatible version.
-fn parse_version(version_str: &str, project: &WorkspaceInfo) -> Result<Version> {
+///
+/// Projects that aren't JavaScript (including ones we couldn't find a manifest for)
+/// are assumed to use semver.
+fn parse_version(version_str: &str, project: Option<&WorkspaceInfo>) -> Result<Version> {
     let version_str = if version_str.starts_with('v') {
         version_str.strip_prefix('v').unwrap()
     } else {
         version_str
     };
 
-    match project.kind {
-        WorkspaceKind::Rust => {
+    match project.map(|p| &p.kind) {
+        Some(WorkspaceKind::Rust) | None => {
             let version = semver::Version::parse(version_str).map_err(|_| {
                 OrandaError::PackageVersionParse {
                     version: version_str.to_owned(),
@@ -125,7 +451,7 @@ fn parse_version(version_str: &str, project: &WorkspaceInfo) -> Result<Version>
             })?;
             Ok(Version::Cargo(version))
         }
-        WorkspaceKind::Javascript => {
+        Some(WorkspaceKind::Javascript) => {
             let version = node_semver::Version::parse(version_str).map_err(|_| {
                 OrandaError::PackageVersionParse {
                     version: version_str.to_owned(),
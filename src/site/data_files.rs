@@ -0,0 +1,139 @@
+//! Data files (JSON, YAML or TOML) from `build.data_dir`, which templates get under `data`.
+//!
+//! Each file is named after its path in the directory, without the extension, so
+//! `data/features.json` is `data.features` and `data/team/members.yml` is `data.team.members`.
+
+use axoasset::SourceFile;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::{Map, Value};
+
+use crate::config::BuildConfig;
+use crate::errors::*;
+
+/// Load the data files for a site
+///
+/// If `build.data_dir` isn't set, the `data/` directory we look in instead might belong to
+/// something else entirely, so files in it that don't parse are only warned about.
+pub fn load_for_build(build: &BuildConfig) -> Result<Value> {
+    load(Utf8Path::new(build.data_dir()), build.data_dir.is_some())
+}
+
+/// Load every data file in a directory (which doesn't have to exist)
+///
+/// Files that don't parse are errors if `strict` is set, and are skipped with a warning otherwise.
+pub fn load(data_dir: &Utf8Path, strict: bool) -> Result<Value> {
+    if !data_dir.is_dir() {
+        return Ok(Value::Object(Map::new()));
+    }
+    Ok(Value::Object(load_dir(data_dir, strict)?))
+}
+
+fn load_dir(dir: &Utf8Path, strict: bool) -> Result<Map<String, Value>> {
+    let mut entries = dir
+        .read_dir_utf8()?
+        .map(|entry| entry.map(|entry| entry.into_path()))
+        .collect::<std::io::Result<Vec<Utf8PathBuf>>>()?;
+    // Sort them, so that which of two files with the same name wins doesn't depend on the OS
+    entries.sort();
+
+    let mut data = Map::new();
+    for path in entries {
+        let (name, value) = if path.is_dir() {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            (name.to_owned(), Value::Object(load_dir(&path, strict)?))
+        } else {
+            let Some(name) = path.file_stem() else {
+                continue;
+            };
+            let value = match load_file(&path) {
+                Err(OrandaError::DataFileParseFailed { details, .. }) if !strict => {
+                    tracing::warn!(
+                        "{} in your data directory couldn't be parsed and will be skipped: {}",
+                        path,
+                        details
+                    );
+                    continue;
+                }
+                result => result?,
+            };
+            let Some(value) = value else {
+                tracing::warn!(
+                    "{} in your data directory isn't JSON, YAML or TOML and will be skipped",
+                    path
+                );
+                continue;
+            };
+            (name.to_owned(), value)
+        };
+        if data.contains_key(&name) {
+            tracing::warn!(
+                "{} has the same name as another file in your data directory and will be skipped",
+                path
+            );
+            continue;
+        }
+        data.insert(name, value);
+    }
+    Ok(data)
+}
+
+/// Parse a data file, if it's a kind we know
+fn load_file(path: &Utf8Path) -> Result<Option<Value>> {
+    let extension = path.extension().map(|ext| ext.to_lowercase());
+    let parse = match extension.as_deref() {
+        Some("json") => parse_json,
+        Some("yml" | "yaml") => parse_yaml,
+        Some("toml") => parse_toml,
+        _ => return Ok(None),
+    };
+    let source = SourceFile::load_local(path)?;
+    parse(source.contents())
+        .map(Some)
+        .map_err(|(details, line_col)| OrandaError::DataFileParseFailed {
+            path: path.to_owned(),
+            span: line_col.and_then(|(line, col)| span_near(&source, line, col)),
+            file: source,
+            details,
+        })
+}
+
+/// The span of a position in a file, or the nearest thing to it
+///
+/// Parsers like to point just past the end of a line (or the file) when something's missing.
+fn span_near(source: &SourceFile, line: usize, col: usize) -> Option<miette::SourceSpan> {
+    source
+        .span_for_line_col(line, col)
+        .or_else(|| {
+            let text = source.contents().lines().nth(line.checked_sub(1)?)?;
+            source.span_for_line_col(line, text.len())
+        })
+        .or_else(|| {
+            let len = source.contents().trim_end().len();
+            (len > 0).then(|| (len - 1..len).into())
+        })
+}
+
+/// What went wrong while parsing, and where (as a 1-based line and column)
+type ParseError = (String, Option<(usize, usize)>);
+
+fn parse_json(contents: &str) -> std::result::Result<Value, ParseError> {
+    serde_json::from_str(contents).map_err(|e| (e.to_string(), Some((e.line(), e.column()))))
+}
+
+fn parse_yaml(contents: &str) -> std::result::Result<Value, ParseError> {
+    serde_yaml::from_str(contents).map_err(|e| {
+        let location = e.location().map(|l| (l.line(), l.column()));
+        (e.to_string(), location)
+    })
+}
+
+fn parse_toml(contents: &str) -> std::result::Result<Value, ParseError> {
+    let value = toml::from_str::<toml::Value>(contents).map_err(|e| {
+        // toml's positions start at 0
+        let location = e.line_col().map(|(line, col)| (line + 1, col + 1));
+        (e.to_string(), location)
+    })?;
+    serde_json::to_value(value).map_err(|e| (e.to_string(), None))
+}
@@ -0,0 +1,108 @@
+//! A [JSON Feed](https://www.jsonfeed.org/version/1.1/) of the changelog
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::errors::Result;
+use crate::site::changelog::{feed_releases, ChangelogContext};
+use crate::site::link::generate_absolute;
+
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: String,
+    pub description: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    pub authors: Vec<JsonFeedAuthor>,
+    pub items: Vec<JsonFeedItem>,
+    /// Our own fields, which JSON Feed wants prefixed with an underscore
+    #[serde(rename = "_oranda")]
+    pub oranda: JsonFeedExtension,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedExtension {
+    /// How many pages the changelog on the site is split into
+    pub page_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedAuthor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub content_html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The Keep a Changelog categories of the release
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_published: Option<DateTime<FixedOffset>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<JsonFeedAuthor>,
+}
+
+impl JsonFeed {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+pub fn generate_json_feed(context: &ChangelogContext, config: &Config) -> Result<JsonFeed> {
+    let items = feed_releases(context, config)
+        .iter()
+        .map(|release| {
+            let url =
+                generate_absolute(config, &format!("changelog/{}", release.version_tag)).unwrap();
+            JsonFeedItem {
+                id: url.clone(),
+                url,
+                title: release.name.clone().unwrap_or(release.version_tag.clone()),
+                content_html: release.body.clone(),
+                summary: release.summary.clone(),
+                tags: release
+                    .category_counts
+                    .keys()
+                    .map(|category| category.heading().to_lowercase())
+                    .collect(),
+                date_published: release.date,
+                authors: release
+                    .contributors
+                    .iter()
+                    .map(|name| JsonFeedAuthor {
+                        name: name.clone(),
+                        url: None,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(JsonFeed {
+        version: JSON_FEED_VERSION,
+        title: format!("{} Changelog", &config.project.name),
+        description: format!("Changelog information for {}", &config.project.name),
+        home_page_url: generate_absolute(config, "changelog").unwrap(),
+        feed_url: generate_absolute(config, "changelog.json").unwrap(),
+        authors: vec![JsonFeedAuthor {
+            name: config.project.name.clone(),
+            url: config.project.repository.clone(),
+        }],
+        items,
+        oranda: JsonFeedExtension {
+            page_count: context.page_count,
+        },
+    })
+}
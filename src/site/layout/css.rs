@@ -2,9 +2,11 @@ use std::env;
 use std::sync::RwLock;
 
 use crate::errors::*;
+use crate::http_cache;
 
 use crate::config::style::ORANDA_CSS_TAG;
-use axoasset::{Asset, LocalAsset};
+use crate::site::oranda_theme::CustomTheme;
+use axoasset::LocalAsset;
 use camino::Utf8Path;
 use minifier::css;
 
@@ -18,7 +20,7 @@ struct CssItem {
 fn concat_minify(css_files: &[String]) -> Result<String> {
     let mut css = String::new();
     for file in css_files {
-        let future = Asset::load_string(file);
+        let future = http_cache::load_string(file);
         let unminified = tokio::runtime::Handle::current().block_on(future)?;
         let minified = match css::minify(&unminified) {
             Ok(css) => Ok(css),
@@ -110,12 +112,9 @@ fn fetch_css(dist_dir: &str, release_tag: &str) -> Result<()> {
 }
 
 async fn fetch_oranda(release_tag: &str) -> Result<String> {
-    let oranda_css_request =
-        octolotl::request::ReleaseAsset::new("axodotdev", "oranda", release_tag, "oranda.css");
-    Ok(octolotl::Request::send(&oranda_css_request, true)
-        .await?
-        .text()
-        .await?)
+    let url =
+        format!("https://github.com/axodotdev/oranda/releases/download/{release_tag}/oranda.css");
+    Ok(http_cache::get(&url).await?.text())
 }
 
 fn get_css_filename(release_tag: &str) -> String {
@@ -132,3 +131,8 @@ pub fn write_additional_css(additional_css: &[String], dist_dir: &Utf8Path) -> R
     LocalAsset::write_new(&minified_css, dist_dir.join("custom.css"))?;
     Ok(())
 }
+
+pub fn write_custom_theme_css(custom_theme: &CustomTheme, dist_dir: &Utf8Path) -> Result<()> {
+    LocalAsset::write_new(&custom_theme.site_css()?, dist_dir.join("theme.css"))?;
+    Ok(())
+}
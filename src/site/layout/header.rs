@@ -1,8 +1,9 @@
 use crate::config::Config;
 use crate::errors::*;
+use crate::http_cache;
 use crate::site::link;
 
-use axoasset::{Asset, LocalAsset};
+use axoasset::LocalAsset;
 
 const DEFAULT_FAVICON: &[u8] = include_bytes!("../../../assets/favicon.ico");
 
@@ -26,10 +27,10 @@ async fn fetch_logo(
     dist_dir: &str,
     origin_path: &str,
 ) -> Result<String> {
-    let copy_result = Asset::copy(origin_path, dist_dir).await?;
+    let copy_result = http_cache::copy(origin_path, dist_dir).await?;
 
-    let path_as_string = copy_result.strip_prefix(dist_dir)?.to_string_lossy();
-    let src = link::generate_relative(path_prefix, &path_as_string);
+    let path_as_string = copy_result.strip_prefix(dist_dir)?.as_str();
+    let src = link::generate_relative(path_prefix, path_as_string);
 
     Ok(src)
 }
@@ -16,7 +16,7 @@ let options = {
     linuxArm: "aarch64-unknown-linux",
 
     // ios: "ios",
-    // android: "linux-android",
+    android: "-linux-android",
     // freebsd: "freebsd",
 };
 
@@ -101,6 +101,11 @@ function getOS() {
         // FIXME: Can we find out whether linux 32-bit or ARM are used?
     }
 
+    // android (which also claims to be linux, so this needs to go after that)
+    if (userAgent.includes("Android")) {
+        OS = options.android;
+    }
+
     // if (
     //     userAgent.includes("iPad") ||
     //     userAgent.includes("iPhone") ||
@@ -115,8 +120,25 @@ function getOS() {
     return OS;
 }
 
+// Linux distros whose browsers are known to mention them in the user agent,
+// keyed by their ArtifactSystem name
+let distros = {
+    linuxubuntu: "Ubuntu",
+    linuxdebian: "Debian",
+    linuxfedora: "Fedora",
+    linuxredhat: "Red Hat",
+    linuxsuse: "SUSE",
+    linuxmandriva: "Mandriva",
+};
+
+function getDistro() {
+    var userAgent = navigator.userAgent;
+    return Object.keys(distros).find((distro) => userAgent.includes(distros[distro]));
+}
+
 let os = getOS();
 window.os = os;
+let distro = os && os.includes("linux") ? getDistro() : undefined;
 
 // Unhide and hydrate selector with events
 const archSelect = document.querySelector(".arch-select");
@@ -199,6 +221,16 @@ let hit = allPlatforms.find(
     }
 );
 
+// Prefer the entry for the user's Linux distro, if there is one
+if (hit && distro) {
+    const distroHit = allPlatforms.find(
+        (a) => a.dataset.arch.includes(os) && a.dataset.distro === distro
+    );
+    if (distroHit) {
+        hit = distroHit;
+    }
+}
+
 if (hit) {
     hit.classList.remove("hidden");
     const selectEl = document.querySelector("#install-arch-select");
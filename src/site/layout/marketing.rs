@@ -0,0 +1,120 @@
+//! The parts of the layout that `marketing` controls: the announcement banner, the repo
+//! banner and the footer.
+
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, FooterColumn, RepoForge};
+use crate::errors::*;
+use crate::site::layout::header::get_logo;
+use crate::site::markdown;
+
+/// The announcement banner
+#[derive(Serialize, Debug)]
+pub struct AnnouncementContext {
+    /// Identifies this announcement, so dismissing it doesn't dismiss the next one
+    id: String,
+    html: String,
+    /// The last day to show it on (YYYY-MM-DD)
+    expires: Option<String>,
+    dismissible: bool,
+}
+
+/// The link to the repository
+#[derive(Serialize, Debug)]
+pub struct RepoContext {
+    url: String,
+    forge: RepoForge,
+    /// A custom icon to show instead of the forge's
+    icon: Option<String>,
+    /// The text of the banner, if it's enabled
+    banner_text: Option<String>,
+}
+
+/// The footer
+#[derive(Serialize, Debug, Default)]
+pub struct FooterContext {
+    /// Rendered `marketing.footer.text`, which replaces the name and license
+    html: Option<String>,
+    columns: Vec<FooterColumn>,
+}
+
+impl AnnouncementContext {
+    /// The announcement to show, if there is one and it hasn't expired yet
+    pub fn new(config: &Config) -> Result<Option<Self>> {
+        let Some(announcement) = &config.marketing.announcement else {
+            return Ok(None);
+        };
+        if announcement.text.trim().is_empty() {
+            return Ok(None);
+        }
+        if let Some(expires) = &announcement.expires {
+            let date = NaiveDate::parse_from_str(expires, "%Y-%m-%d").map_err(|_| {
+                OrandaError::InvalidAnnouncementExpiry {
+                    date: expires.clone(),
+                }
+            })?;
+            if date < chrono::Utc::now().date_naive() {
+                return Ok(None);
+            }
+        }
+        let id = Sha256::digest(announcement.text.as_bytes())
+            .iter()
+            .take(8)
+            .fold(String::new(), |mut id, byte| {
+                let _ = write!(id, "{byte:02x}");
+                id
+            });
+        Ok(Some(Self {
+            id,
+            html: markdown::to_html(&announcement.text, &config.styles.syntax_theme)?,
+            expires: announcement.expires.clone(),
+            dismissible: announcement.dismissible,
+        }))
+    }
+}
+
+impl RepoContext {
+    pub fn new(config: &Config) -> Result<Option<Self>> {
+        let Some(url) = &config.project.repository else {
+            return Ok(None);
+        };
+        let banner = config.marketing.repo_banner.as_ref();
+        let forge = banner
+            .and_then(|banner| banner.forge)
+            .unwrap_or_else(|| RepoForge::detect(url, config.components.source.as_ref()));
+        let icon = match banner.and_then(|banner| banner.icon.as_deref()) {
+            Some(icon) => Some(get_logo(icon, config)?),
+            None => None,
+        };
+        let banner_text = banner.map(|banner| {
+            banner
+                .text
+                .clone()
+                .unwrap_or_else(|| format!("Check out our {}!", forge.display_name()))
+        });
+        Ok(Some(Self {
+            url: url.clone(),
+            forge,
+            icon,
+            banner_text,
+        }))
+    }
+}
+
+impl FooterContext {
+    pub fn new(config: &Config) -> Result<Self> {
+        let footer = &config.marketing.footer;
+        let html = match &footer.text {
+            Some(text) => Some(markdown::to_html(text, &config.styles.syntax_theme)?),
+            None => None,
+        };
+        Ok(Self {
+            html,
+            columns: footer.columns.clone(),
+        })
+    }
+}
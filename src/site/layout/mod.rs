@@ -1,27 +1,37 @@
-use crate::config::{Config, SocialConfig};
+use crate::config::{Config, NavBuiltin, SocialConfig};
 use crate::errors::*;
-use crate::site::oranda_theme::OrandaTheme;
+use crate::site::mdbook::AxomdbookTheme;
+use crate::site::oranda_theme::{ColorScheme, OrandaTheme};
 use serde::Serialize;
 
 pub mod css;
 pub mod header;
 pub mod javascript;
+pub mod marketing;
 use crate::data::Context;
 use crate::site::layout::header::get_logo;
 use crate::site::{link, page};
 use javascript::analytics::Analytics;
+use marketing::{AnnouncementContext, FooterContext, RepoContext};
 
 #[derive(Serialize, Debug, Default)]
 pub struct LayoutContext {
     /// Result of [`OrandaTheme::as_css_classes`][]
     theme: &'static str,
+    /// The themes to switch between, if pages follow the visitor's light/dark preference
+    color_scheme: Option<ColorSchemeContext>,
     project_name: String,
     homepage: Option<String>,
     repository: Option<String>,
+    /// The repository link and banner, if there's a repository
+    repo: Option<RepoContext>,
+    announcement: Option<AnnouncementContext>,
+    footer: FooterContext,
     favicon_url: String,
     description: Option<String>,
     oranda_css_path: String,
     has_additional_css: bool,
+    has_custom_theme: bool,
     logo: Option<String>,
     license: Option<String>,
     additional_pages: Option<Vec<AdditionalPageContext>>,
@@ -29,6 +39,9 @@ pub struct LayoutContext {
     mdbook_link: Option<String>,
     funding_link: Option<String>,
     changelog_link: Option<String>,
+    /// Everything in the nav, in order
+    nav: Vec<NavLink>,
+    feeds: Vec<FeedLink>,
     has_nav: bool,
     home_link: String,
     path_prefix: Option<String>,
@@ -36,6 +49,69 @@ pub struct LayoutContext {
     social: SocialConfig,
 }
 
+/// The light and dark versions of the theme
+#[derive(Serialize, Debug, Default)]
+pub struct ColorSchemeContext {
+    /// Result of [`OrandaTheme::as_css_classes`][] for the light version
+    light: &'static str,
+    /// Result of [`OrandaTheme::as_css_classes`][] for the dark version
+    dark: &'static str,
+    /// Result of [`AxomdbookTheme::buttons_label`][], so we notice the same changes as the mdbook
+    label: String,
+}
+
+impl ColorSchemeContext {
+    pub fn new(config: &Config) -> Option<Self> {
+        let theme = config.styles.theme;
+        if config.styles.color_scheme != ColorScheme::Auto {
+            return None;
+        }
+        let Some(twin) = theme.twin_theme() else {
+            tracing::warn!(
+                "styles.theme has no light/dark twin, so styles.color_scheme will have no effect"
+            );
+            return None;
+        };
+        let (light, dark) = if theme.is_dark() {
+            (twin, theme)
+        } else {
+            (theme, twin)
+        };
+        Some(Self {
+            light: light.as_css_classes(),
+            dark: dark.as_css_classes(),
+            label: AxomdbookTheme::from_oranda_theme(&theme)
+                .map(|theme| theme.buttons_label())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// A link in the nav
+#[derive(Serialize, Debug, Default)]
+pub struct NavLink {
+    label: String,
+    url: String,
+    icon: Option<String>,
+    /// Whether this goes to another site
+    is_external: bool,
+}
+
+/// Something in the nav that `build.nav` can refer to
+#[derive(Debug, PartialEq)]
+enum NavTarget<'a> {
+    Builtin(NavBuiltin),
+    Page(&'a str),
+}
+
+/// A changelog feed, advertised with a `<link rel="alternate">` tag
+#[derive(Serialize, Debug, Default)]
+pub struct FeedLink {
+    mime_type: &'static str,
+    title: String,
+    url: String,
+}
+
 #[derive(Serialize, Debug, Default)]
 pub struct AdditionalPageContext {
     path: String,
@@ -46,18 +122,24 @@ impl LayoutContext {
     pub fn new(config: &Config, context: Option<&Context>) -> Result<Self> {
         let css_path =
             css::get_css_link(&config.build.path_prefix, &config.styles.oranda_css_version)?;
+        let mut additional_page_targets = Vec::new();
         let additional_pages = if config.build.additional_pages.is_empty() {
             None
         } else {
             let mut ret = Vec::new();
             for (name, path) in config.build.additional_pages.iter() {
-                if page::source::is_markdown(path) {
+                if page::source::is_markdown(path) || page::source::is_template(path) {
                     let file_path = page::source::get_filename_with_dir(path)?;
                     if let Some(path) = file_path {
                         let href = link::generate_relative(
                             &config.build.path_prefix,
                             &format!("{}/", path),
                         );
+                        additional_page_targets.push((
+                            NavTarget::Page(name),
+                            name.clone(),
+                            href.clone(),
+                        ));
                         ret.push(AdditionalPageContext {
                             name: name.clone(),
                             path: href,
@@ -91,7 +173,8 @@ impl LayoutContext {
             .funding
             .as_ref()
             .map(|_| link::generate_relative(&config.build.path_prefix, "funding/"));
-        let changelog_link = if context.is_some() {
+        // A changelog made from releases needs there to be releases, one made from the file doesn't
+        let changelog_link = if context.is_some() || !config.components.changelog_from_releases() {
             config
                 .components
                 .changelog
@@ -100,34 +183,84 @@ impl LayoutContext {
         } else {
             None
         };
-        let has_nav = additional_pages.is_some()
-            || artifacts_link.is_some()
-            || mdbook_link.is_some()
-            || funding_link.is_some()
-            || changelog_link.is_some();
+        let mut feeds = Vec::new();
+        if let (Some(changelog), Some(_)) = (&config.components.changelog, &changelog_link) {
+            let title = format!("{} Changelog", &config.project.name);
+            let enabled = [
+                (changelog.rss_feed, "application/rss+xml", "changelog.rss"),
+                (
+                    changelog.atom_feed,
+                    "application/atom+xml",
+                    "changelog.atom",
+                ),
+                (
+                    changelog.json_feed,
+                    "application/feed+json",
+                    "changelog.json",
+                ),
+            ];
+            for (_, mime_type, filename) in enabled.into_iter().filter(|(on, ..)| *on) {
+                feeds.push(FeedLink {
+                    mime_type,
+                    title: title.clone(),
+                    url: link::generate_relative(&config.build.path_prefix, filename),
+                });
+            }
+        }
         let home_link = if let Some(path_prefix) = config.build.path_prefix.as_ref() {
             format!("/{}/", path_prefix)
         } else {
             "/".to_string()
         };
+        // Everything that can be in the nav, in its usual order
+        let mut nav_targets = vec![(
+            NavTarget::Builtin(NavBuiltin::Home),
+            NavBuiltin::Home.label().to_owned(),
+            home_link.clone(),
+        )];
+        nav_targets.extend(additional_page_targets);
+        for (builtin, link) in [
+            (NavBuiltin::Install, &artifacts_link),
+            (NavBuiltin::Docs, mdbook_link),
+            (NavBuiltin::Funding, funding_link),
+            (NavBuiltin::Changelog, &changelog_link),
+        ] {
+            if let Some(link) = link {
+                nav_targets.push((
+                    NavTarget::Builtin(builtin),
+                    builtin.label().to_owned(),
+                    link.clone(),
+                ));
+            }
+        }
+        let nav = build_nav(config, nav_targets)?;
+        // The nav isn't worth showing if it's just "Home"
+        let has_nav = nav.len() > 1 || nav.iter().any(|link| link.url != home_link);
         let analytics = Analytics::new(&config.marketing.analytics);
 
         Ok(Self {
             theme: config.styles.theme.as_css_classes(),
+            color_scheme: ColorSchemeContext::new(config),
             project_name: config.project.name.clone(),
             homepage: config.project.homepage.clone(),
             repository: config.project.repository.clone(),
+            repo: RepoContext::new(config)?,
+            announcement: AnnouncementContext::new(config)?,
+            footer: FooterContext::new(config)?,
             favicon_url,
             description: config.project.description.clone(),
             logo,
             license: config.project.license.clone(),
             oranda_css_path: css_path,
             has_additional_css: !config.styles.additional_css.is_empty(),
+            has_custom_theme: config.styles.custom_theme.is_some(),
             additional_pages,
             artifacts_link,
             mdbook_link: mdbook_link.clone(),
             funding_link: funding_link.clone(),
             changelog_link: changelog_link.clone(),
+            nav,
+            feeds,
             has_nav,
             home_link,
             path_prefix: config.build.path_prefix.clone(),
@@ -147,12 +280,84 @@ impl LayoutContext {
             theme: workspace_config.styles.theme.as_css_classes(),
             oranda_css_path: css_path,
             has_additional_css: !workspace_config.styles.additional_css.is_empty(),
+            has_custom_theme: workspace_config.styles.custom_theme.is_some(),
             path_prefix: workspace_config.build.path_prefix.clone(),
             ..Default::default()
         })
     }
 }
 
+/// Put the nav together: the items in `build.nav` in order, with everything it doesn't mention
+/// in its usual place (by index, so that an empty `build.nav` gives the usual nav)
+fn build_nav(config: &Config, targets: Vec<(NavTarget, String, String)>) -> Result<Vec<NavLink>> {
+    let mut nav = Vec::new();
+    let mut mentioned = Vec::new();
+    for (idx, item) in config.build.nav.iter().enumerate() {
+        let invalid = |details: &str| OrandaError::InvalidNavItem {
+            index: idx + 1,
+            details: details.to_owned(),
+        };
+        let icon = match &item.icon {
+            Some(icon) => Some(get_logo(icon, config)?),
+            None => None,
+        };
+        let target = match (item.builtin, &item.page, &item.url) {
+            (Some(builtin), None, None) => NavTarget::Builtin(builtin),
+            (None, Some(page), None) => {
+                if !config.build.additional_pages.contains_key(page) {
+                    return Err(invalid(&format!(
+                        "there's no additional page called \"{page}\""
+                    )));
+                }
+                NavTarget::Page(page)
+            }
+            (None, None, Some(url)) => {
+                let label = item
+                    .label
+                    .clone()
+                    .ok_or_else(|| invalid("external links need a label"))?;
+                nav.push(NavLink {
+                    label,
+                    url: url.clone(),
+                    icon,
+                    is_external: true,
+                });
+                continue;
+            }
+            _ => {
+                return Err(invalid(
+                    "it needs exactly one of `builtin`, `page` or `url`",
+                ))
+            }
+        };
+        // Pages we aren't building (like "install" without any artifacts) are left out
+        if let Some((_, label, url)) = targets.iter().find(|(t, _, _)| *t == target) {
+            nav.push(NavLink {
+                label: item.label.clone().unwrap_or_else(|| label.clone()),
+                url: url.clone(),
+                icon,
+                is_external: false,
+            });
+        }
+        mentioned.push(target);
+    }
+    for (idx, (target, label, url)) in targets.into_iter().enumerate() {
+        if mentioned.contains(&target) {
+            continue;
+        }
+        nav.insert(
+            idx.min(nav.len()),
+            NavLink {
+                label,
+                url,
+                icon: None,
+                is_external: false,
+            },
+        );
+    }
+    Ok(nav)
+}
+
 impl OrandaTheme {
     /// Gets the css classes this theme lowers to
     pub fn as_css_classes(&self) -> &'static str {
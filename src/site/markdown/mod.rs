@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 mod syntax_highlight;
@@ -8,7 +9,8 @@ use crate::errors::*;
 
 use ammonia::Builder;
 use comrak::adapters::SyntaxHighlighterAdapter;
-use comrak::{self, ComrakOptions, ComrakPlugins};
+use comrak::nodes::{Ast, AstNode, NodeLink, NodeValue};
+use comrak::{self, Arena, ComrakOptions, ComrakPlugins};
 
 pub struct Adapters<'a> {
     syntax_theme: &'a SyntaxTheme,
@@ -47,17 +49,85 @@ fn initialize_comrak_options() -> ComrakOptions {
     options
 }
 
+/// A piece of text, which may have been turned into a link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextSpan {
+    Text(String),
+    Link { text: String, url: String },
+}
+
 pub fn to_html(markdown: &str, syntax_theme: &SyntaxTheme) -> Result<String> {
+    to_html_with_links(markdown, syntax_theme, |text| {
+        vec![TextSpan::Text(text.to_owned())]
+    })
+}
+
+/// Like [`to_html`][], but lets `linkify` turn parts of the plain text into links
+///
+/// Text that's already in a link or in code is left alone.
+pub fn to_html_with_links(
+    markdown: &str,
+    syntax_theme: &SyntaxTheme,
+    linkify: impl Fn(&str) -> Vec<TextSpan>,
+) -> Result<String> {
     let options = initialize_comrak_options();
 
     let mut plugins = ComrakPlugins::default();
     let adapter = Adapters { syntax_theme };
     plugins.render.codefence_syntax_highlighter = Some(&adapter);
 
-    let unsafe_html = comrak::markdown_to_html_with_plugins(markdown, &options, &plugins);
+    let arena = Arena::new();
+    let root = comrak::parse_document(&arena, markdown, &options);
+    let text_nodes = root
+        .descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Text(_)))
+        .filter(|node| {
+            !node.ancestors().any(|ancestor| {
+                matches!(
+                    ancestor.data.borrow().value,
+                    NodeValue::Link(_) | NodeValue::Image(_)
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    for node in text_nodes {
+        let spans = match &node.data.borrow().value {
+            NodeValue::Text(text) => linkify(text),
+            _ => continue,
+        };
+        if spans.iter().all(|span| matches!(span, TextSpan::Text(_))) {
+            continue;
+        }
+        for span in spans {
+            let new_node = match span {
+                TextSpan::Text(text) => new_node(&arena, NodeValue::Text(text)),
+                TextSpan::Link { text, url } => {
+                    let link = new_node(
+                        &arena,
+                        NodeValue::Link(NodeLink {
+                            url,
+                            title: String::new(),
+                        }),
+                    );
+                    link.append(new_node(&arena, NodeValue::Text(text)));
+                    link
+                }
+            };
+            node.insert_before(new_node);
+        }
+        node.detach();
+    }
+
+    let mut unsafe_html = vec![];
+    comrak::format_html_with_plugins(root, &options, &mut unsafe_html, &plugins)?;
+    let unsafe_html = String::from_utf8_lossy(&unsafe_html);
     let safe_html = Builder::new()
         .add_generic_attributes(&["style", "class", "id"])
         .clean(&unsafe_html)
         .to_string();
     Ok(safe_html)
 }
+
+fn new_node<'a>(arena: &'a Arena<AstNode<'a>>, value: NodeValue) -> &'a AstNode<'a> {
+    arena.alloc(AstNode::new(RefCell::new(Ast::new(value))))
+}
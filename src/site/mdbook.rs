@@ -6,7 +6,8 @@ use std::path::PathBuf;
 use crate::config::MdBookConfig;
 use crate::data::workspaces::WorkspaceData;
 use crate::errors::*;
-use crate::site::{oranda_theme::OrandaTheme, Site};
+use crate::site::oranda_theme::{ColorScheme, CustomTheme, OrandaTheme};
+use crate::site::Site;
 
 use super::markdown::SyntaxTheme;
 use crate::paths::determine_path;
@@ -100,6 +101,19 @@ impl AxomdbookTheme {
         }
     }
 
+    /// Get the oranda theme this is equivalent to
+    pub fn oranda_theme(&self) -> OrandaTheme {
+        use AxomdbookTheme::*;
+        match self {
+            DefaultLight => OrandaTheme::Light,
+            Default => OrandaTheme::Dark,
+            AxoDark => OrandaTheme::AxoDark,
+            AxoLight => OrandaTheme::AxoLight,
+            Hacker => OrandaTheme::Hacker,
+            Cupcake => OrandaTheme::Cupcake,
+        }
+    }
+
     /// Get whether this theme should be presented as a "dark mode" or "light mode"
     pub fn is_dark(&self) -> bool {
         use AxomdbookTheme::*;
@@ -140,6 +154,17 @@ impl AxomdbookTheme {
         }
     }
 
+    /// The names of the theme buttons we add for this theme, which the theme selector uses to
+    /// notice the theme changing (and the visitor's old choice no longer applying)
+    ///
+    /// oranda's own pages use this too, since they share that choice with the mdbook.
+    pub fn buttons_label(&self) -> String {
+        match self.twin_theme() {
+            Some(twin) => format!("{} / {}", self.name(), twin.name()),
+            None => self.name().to_owned(),
+        }
+    }
+
     pub fn name(&self) -> &'static str {
         use AxomdbookTheme::*;
         match self {
@@ -200,6 +225,8 @@ pub fn build_mdbook(
     dist: &Utf8Path,
     book_cfg: &MdBookConfig,
     oranda_theme: &OrandaTheme,
+    color_scheme: &ColorScheme,
+    palette: Option<&CustomTheme>,
     syntax_theme: &SyntaxTheme,
 ) -> Result<()> {
     // Read mdbook's config to inherit the user's setup
@@ -222,17 +249,19 @@ pub fn build_mdbook(
     let theme_dir = custom_theme_dir(book_cfg, dist)?;
     if let Some(theme) = custom_theme {
         // Create all the files for our custom theme
-        init_theme_dir(&theme_dir, theme)?;
-
-        // Tell mdbook to default to our theme, forcing both the light and dark modes
-        //
-        // FIXME(#314): for now we force the same theme as both the "light" and "dark" version
-        // to avoid clashes between the main oranda pages and the mdbook when the two
-        // disagree on how "dark mode" is detected. In the future we can/should use twin_theme
-        // to properly set these values.
-        let dark_theme = theme;
+        init_theme_dir(&theme_dir, theme, palette)?;
+
+        // Tell mdbook to default to our theme. Unless the oranda pages follow the visitor's
+        // light/dark preference too, we force the same theme as both the "light" and "dark"
+        // version, to avoid clashes between the main oranda pages and the mdbook when the
+        // two disagree on which one to show.
+        let (light_theme, dark_theme) = match (color_scheme, theme.twin_theme()) {
+            (ColorScheme::Auto, Some(twin)) if theme.is_dark() => (twin, theme),
+            (ColorScheme::Auto, Some(twin)) => (theme, twin),
+            _ => (theme, theme),
+        };
         md.config
-            .set("output.html.default-theme", theme.class())
+            .set("output.html.default-theme", light_theme.class())
             .expect("failed to convert theme name to a TOML String");
         md.config
             .set("output.html.preferred-dark-theme", dark_theme.class())
@@ -298,7 +327,11 @@ pub fn homogenize_path(path: &PathBuf) -> PathBuf {
 ///
 /// Note that these files assume you will also call [`add_custom_syntax_theme_to_output`][]
 /// to add axo-highlight.css to the build dir.
-fn init_theme_dir(theme_dir: &Utf8Path, theme: AxomdbookTheme) -> Result<()> {
+fn init_theme_dir(
+    theme_dir: &Utf8Path,
+    theme: AxomdbookTheme,
+    palette: Option<&CustomTheme>,
+) -> Result<()> {
     // Just to be safe, clear out the theme dir in case it still exists
     delete_theme_dir(theme_dir)?;
 
@@ -315,7 +348,11 @@ fn init_theme_dir(theme_dir: &Utf8Path, theme: AxomdbookTheme) -> Result<()> {
             },
         )
         .expect("failed to find axomdbook theme for mdbook!?");
-    let variables = THEME_VARIABLES_CSS.replace(KEY_ORANDA_VARS, theme_vars);
+    let mut variables = THEME_VARIABLES_CSS.replace(KEY_ORANDA_VARS, theme_vars);
+    // Then the user's own colors and fonts, which win by coming later
+    if let Some(palette) = palette {
+        variables.push_str(&palette.mdbook_css()?);
+    }
 
     // Substitute in buttons for the selected theme
     let mut buttons = String::new();
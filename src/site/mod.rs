@@ -1,16 +1,15 @@
 use std::path::Path;
 
-use axoasset::{Asset, LocalAsset};
-use axoproject::GithubRepo;
+use axoasset::LocalAsset;
 use camino::{Utf8Path, Utf8PathBuf};
 use indexmap::IndexMap;
 use minijinja::context;
 use tracing::instrument;
 
-use crate::config::{AxoprojectLayer, Config, ReleasesSource};
-use crate::data::github::GithubRelease;
-use crate::data::{funding::Funding, workspaces, Context};
+use crate::config::{AxoprojectLayer, ChangelogMode, Config, ReleasesSource};
+use crate::data::{funding::Funding, git::GitRelease, workspaces, Context};
 use crate::errors::*;
+use crate::http_cache;
 
 use crate::data::workspaces::WorkspaceData;
 use crate::site::templates::Templates;
@@ -20,8 +19,11 @@ pub use layout::javascript;
 use page::Page;
 
 pub mod artifacts;
+pub mod atom;
 pub mod changelog;
+pub mod data_files;
 pub mod funding;
+pub mod json_feed;
 pub mod layout;
 pub mod link;
 pub mod markdown;
@@ -29,6 +31,7 @@ pub mod mdbook;
 pub mod oranda_theme;
 pub mod page;
 pub mod rss;
+pub mod site_context;
 pub mod templates;
 mod workspace_index;
 
@@ -85,6 +88,9 @@ impl Site {
             &context,
         )?;
         let mut dist = Utf8PathBuf::from(&workspace_config.build.dist_dir);
+        if let Some(custom_theme) = &workspace_config.styles.custom_theme {
+            css::write_custom_theme_css(custom_theme, &dist)?;
+        }
         let additional_css = &workspace_config.styles.additional_css;
         if !additional_css.is_empty() {
             css::write_additional_css(additional_css, &dist)?;
@@ -102,13 +108,28 @@ impl Site {
         }
         css::place_css(&config.build.dist_dir, &config.styles.oranda_css_version)?;
         let needs_context = Self::needs_context(config)?;
-        let context = if needs_context {
+        let mut context = if needs_context {
             Some(Self::build_context(config)?)
         } else {
             None
         };
 
-        let templates = Templates::new(config, context.as_ref())?;
+        if let Some(context) = &mut context {
+            if config.components.artifacts_enabled() {
+                if let Some(latest) = context.latest_mut() {
+                    // Give especially nice treatment to the latest release and make
+                    // its scripts easy to view (others get hotlinked and will just download)
+                    latest.artifacts.make_scripts_viewable(config)?;
+                }
+            }
+        }
+        // Funding is read once, for both its page and the `site` every template gets
+        let funding = match (&context, &config.components.funding) {
+            (Some(_), Some(funding_cfg)) => Some(Funding::new(funding_cfg, &config.styles)?),
+            _ => None,
+        };
+
+        let templates = Templates::new(config, context.as_ref(), funding.as_ref())?;
 
         let mut pages = vec![];
 
@@ -121,48 +142,42 @@ impl Site {
         let mut index = None;
         Self::print_plan(config);
 
-        if let Some(mut context) = context {
-            if config.components.artifacts_enabled() {
-                if let Some(latest) = context.latest_mut() {
-                    // Give especially nice treatment to the latest release and make
-                    // its scripts easy to view (others get hotlinked and will just download)
-                    latest.artifacts.make_scripts_viewable(config)?;
-
-                    let template_context = artifacts::template_context(&context, config)?;
-                    index = Some(Page::new_from_both(
-                        &config.project.readme_path,
-                        "index.html",
-                        &templates,
-                        "index.html",
-                        context!(artifacts => template_context),
-                        config,
-                    )?);
-                    let artifacts_page = Page::new_from_template(
-                        "artifacts.html",
-                        &templates,
-                        "artifacts.html",
-                        &template_context,
-                    )?;
-                    pages.push(artifacts_page);
-                    if let Some(template_context) = template_context {
-                        artifacts::write_artifacts_json(config, &template_context)?;
-                    }
+        if let Some(context) = &context {
+            if config.components.artifacts_enabled() && context.latest().is_some() {
+                let template_context = artifacts::template_context(context, config)?;
+                index = Some(Page::new_from_both(
+                    &config.project.readme_path,
+                    "index.html",
+                    &templates,
+                    "index.html",
+                    context!(artifacts => template_context),
+                    config,
+                )?);
+                let artifacts_page = Page::new_from_template(
+                    "artifacts.html",
+                    &templates,
+                    "artifacts.html",
+                    &template_context,
+                )?;
+                pages.push(artifacts_page);
+                if let Some(template_context) = template_context {
+                    artifacts::write_artifacts_json(config, &template_context)?;
                 }
             }
-            if config.components.changelog.is_some() {
-                let mut changelog_pages =
-                    Self::build_changelog_pages(&context, &templates, config)?;
-                pages.append(&mut changelog_pages);
-            }
-            if let Some(funding_cfg) = &config.components.funding {
-                let funding = Funding::new(funding_cfg, &config.styles)?;
-                let context = funding::context(funding_cfg, &funding)?;
+            if let (Some(funding_cfg), Some(funding)) = (&config.components.funding, &funding) {
+                let context = funding::context(funding_cfg, funding)?;
                 let page =
                     Page::new_from_template("funding.html", &templates, "funding.html", &context)?;
                 pages.push(page);
             }
         }
 
+        if config.components.changelog.is_some() {
+            let mut changelog_pages =
+                Self::build_changelog_pages(context.as_ref(), &templates, config)?;
+            pages.append(&mut changelog_pages);
+        }
+
         let index = if let Some(index) = index {
             index
         } else {
@@ -220,19 +235,29 @@ impl Site {
     }
 
     fn needs_context(config: &Config) -> Result<bool> {
+        let repo_url = config.project.repository.as_deref();
+        let wants_releases =
+            config.components.artifacts_enabled() || config.components.changelog_from_releases();
+        match config.components.releases_source(repo_url) {
+            // Local releases don't need a repository (or the network) to be read
+            ReleasesSource::Local => return Ok(wants_releases),
+            // Without a repository, git tags are all the releases we can have
+            ReleasesSource::Git if repo_url.is_none() => {
+                let tag_pattern = config
+                    .components
+                    .artifacts
+                    .as_ref()
+                    .and_then(|a| a.tag_pattern.as_deref());
+                return Ok(wants_releases
+                    && !GitRelease::load_all(Utf8Path::new("."), tag_pattern).is_empty());
+            }
+            _ => {}
+        }
+        // Releases are only fetched (once) if something is going to use them
         Ok(config.project.repository.is_some()
             && (config.components.artifacts_enabled()
-                || config.components.changelog.is_some()
-                || config.components.funding.is_some()
-                || Self::has_repo_and_releases(&config.project.repository)?))
-    }
-
-    fn has_repo_and_releases(repo_config: &Option<String>) -> Result<bool> {
-        if let Some(repo) = repo_config {
-            GithubRelease::repo_has_releases(&GithubRepo::from_url(repo)?)
-        } else {
-            Ok(false)
-        }
+                || config.components.changelog_from_releases()
+                || config.components.funding.is_some()))
     }
 
     fn print_plan(config: &Config) {
@@ -265,16 +290,42 @@ impl Site {
     }
 
     fn build_context(config: &Config) -> Result<Context> {
-        let Some(repo_url) = config.project.repository.as_ref() else {
-            return Context::new_current(&config.project, config.components.artifacts.as_ref());
-        };
-        let maybe_ctx = match config.components.source {
-            Some(ReleasesSource::GitHub) | None => Context::new_github(
+        let repo_url = config.project.repository.as_deref();
+        let maybe_ctx = match (config.components.releases_source(repo_url), repo_url) {
+            // Local releases don't need a repository, but can use it for download links
+            (ReleasesSource::Local, repo_url) => Context::new_local(
+                &config.components.local_releases,
                 repo_url,
                 &config.project,
                 config.components.artifacts.as_ref(),
             ),
-            Some(ReleasesSource::Axodotdev) => Context::new_axodotdev(
+            (ReleasesSource::Git, repo_url) => Context::new_git(
+                repo_url,
+                &config.project,
+                config.components.artifacts.as_ref(),
+            ),
+            (_, None) => {
+                return Context::new_current(&config.project, config.components.artifacts.as_ref())
+            }
+            (ReleasesSource::GitHub, Some(repo_url)) => Context::new_github(
+                repo_url,
+                &config.components.github,
+                &config.project,
+                config.components.artifacts.as_ref(),
+            ),
+            (ReleasesSource::GitLab, Some(repo_url)) => Context::new_gitlab(
+                repo_url,
+                &config.components.gitlab,
+                &config.project,
+                config.components.artifacts.as_ref(),
+            ),
+            (ReleasesSource::Forgejo, Some(repo_url)) => Context::new_forgejo(
+                repo_url,
+                &config.components.forgejo,
+                &config.project,
+                config.components.artifacts.as_ref(),
+            ),
+            (ReleasesSource::Axodotdev, Some(repo_url)) => Context::new_axodotdev(
                 &config.project.name,
                 repo_url,
                 &config.project,
@@ -306,9 +357,12 @@ impl Site {
             if page::source::is_markdown(file_path) {
                 let additional_page = Page::new_from_markdown(file_path, templates, config, true)?;
                 pages.push(additional_page)
+            } else if page::source::is_template(file_path) {
+                let additional_page = Page::new_from_user_template(file_path, templates)?;
+                pages.push(additional_page)
             } else {
                 let msg = format!(
-                    "File {} in additional pages is not markdown and will be skipped",
+                    "File {} in additional pages is not markdown or a template and will be skipped",
                     file_path
                 );
                 tracing::warn!("{}", &msg);
@@ -318,47 +372,73 @@ impl Site {
     }
 
     fn build_changelog_pages(
-        context: &Context,
+        context: Option<&Context>,
         templates: &Templates,
         config: &Config,
     ) -> Result<Vec<Page>> {
         let mut pages = vec![];
+        let Some(changelog_config) = &config.components.changelog else {
+            return Ok(pages);
+        };
         // Recompute the axoproject layer here (unfortunately we don't pass it around)
         let cur_dir = std::env::current_dir()?;
         let project = AxoprojectLayer::get_best_workspace(
             &Utf8PathBuf::from_path_buf(cur_dir).expect("Current directory isn't UTF-8?"),
         );
+        let file_context;
+        let context = match changelog_config.mode {
+            ChangelogMode::Releases => match context {
+                Some(context) => context,
+                None => return Ok(pages),
+            },
+            // Every version heading in the changelog file gets an entry, releases or not
+            ChangelogMode::File => {
+                let path = changelog::changelog_file_path(changelog_config, project.as_ref())
+                    .ok_or(OrandaError::ChangelogFileMissing)?;
+                file_context = Context::new_changelog_file(&path, &config.project)?;
+                &file_context
+            }
+        };
         let index_context = changelog::index_context(context, config, project.as_ref())?;
-        let changelog_page = Page::new_from_template(
-            "changelog.html",
-            templates,
-            "changelog_index.html",
-            &index_context,
-        )?;
-        pages.push(changelog_page);
-        if config
-            .components
-            .changelog
-            .clone()
-            .is_some_and(|c| c.rss_feed)
-        {
+        for page_context in changelog::paginate(&index_context, config) {
+            let changelog_page = Page::new_from_template(
+                &changelog::page_filename(page_context.current_page),
+                templates,
+                "changelog_index.html",
+                &page_context,
+            )?;
+            pages.push(changelog_page);
+        }
+        if changelog_config.rss_feed {
             let changelog_rss = rss::generate_rss_feed(&index_context, config)?;
             pages.push(Page {
                 contents: changelog_rss.to_string(),
                 filename: "changelog.rss".to_string(),
             });
         }
-        if !(context.releases.len() == 1 && context.releases[0].source.is_current_state()) {
-            for release in context.releases.iter() {
-                let single_context = changelog::single_context(release, config, project.as_ref());
-                let page = Page::new_from_template(
-                    &format!("changelog/{}.html", single_context.version_tag),
-                    templates,
-                    "changelog_single.html",
-                    &context!(release => single_context),
-                )?;
-                pages.push(page);
-            }
+        if changelog_config.atom_feed {
+            let changelog_atom = atom::generate_atom_feed(&index_context, config)?;
+            pages.push(Page {
+                contents: changelog_atom.to_string(),
+                filename: "changelog.atom".to_string(),
+            });
+        }
+        if changelog_config.json_feed {
+            let changelog_json = json_feed::generate_json_feed(&index_context, config)?;
+            pages.push(Page {
+                contents: changelog_json.to_json()?,
+                filename: "changelog.json".to_string(),
+            });
+        }
+        // These were already made for the index, so don't make them all over again
+        for single_context in &index_context.releases {
+            let page = Page::new_from_template(
+                &format!("changelog/{}.html", single_context.version_tag),
+                templates,
+                "changelog_single.html",
+                &context!(release => single_context),
+            )?;
+            pages.push(page);
         }
         Ok(pages)
     }
@@ -411,11 +491,13 @@ impl Site {
                 &dist,
                 book_cfg,
                 &config.styles.theme,
+                &config.styles.color_scheme,
+                config.styles.custom_theme.as_ref(),
                 &config.styles.syntax_theme,
             )?;
         }
         if let Some(origin_path) = config.styles.favicon.as_ref() {
-            let copy_result_future = Asset::copy(origin_path, &config.build.dist_dir[..]);
+            let copy_result_future = http_cache::copy(origin_path, &config.build.dist_dir[..]);
             tokio::runtime::Handle::current().block_on(copy_result_future)?;
         }
         if Path::new(&config.build.static_dir).exists() {
@@ -423,6 +505,9 @@ impl Site {
         }
         javascript::write_os_script(&dist)?;
 
+        if let Some(custom_theme) = &config.styles.custom_theme {
+            css::write_custom_theme_css(custom_theme, &dist)?;
+        }
         let additional_css = &config.styles.additional_css;
         if !additional_css.is_empty() {
             css::write_additional_css(additional_css, &dist)?;
@@ -1,6 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::*;
+use crate::site::mdbook::AxomdbookTheme;
+
 /// Themes for oranda's output
 #[derive(
     Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
@@ -17,8 +20,186 @@ pub enum OrandaTheme {
     Cupcake,
 }
 
+/// Whether pages use one theme, or follow the visitor's light/dark preference
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    /// Always use `styles.theme`
+    #[default]
+    Fixed,
+    /// Use `styles.theme` or its light/dark twin, depending on the visitor's preference
+    Auto,
+}
+
+impl OrandaTheme {
+    /// Get whether this theme is a "dark mode" or "light mode" one
+    ///
+    /// This and [`OrandaTheme::twin_theme`][] go by the equivalent mdbook theme, so our pages
+    /// and the mdbook always agree.
+    pub fn is_dark(&self) -> bool {
+        AxomdbookTheme::from_oranda_theme(self).map_or(false, |theme| theme.is_dark())
+    }
+
+    /// If this theme is two-in-one with a "dark mode" and "light mode", then this
+    /// returns the other mode.
+    pub fn twin_theme(&self) -> Option<OrandaTheme> {
+        let twin = AxomdbookTheme::from_oranda_theme(self)?.twin_theme()?;
+        Some(twin.oranda_theme())
+    }
+}
+
 impl Default for OrandaTheme {
     fn default() -> Self {
         Self::Dark
     }
 }
+
+/// Colors and fonts to use on top of `styles.theme`
+///
+/// Each of these is put into CSS as it is, so colors can be anything CSS understands
+/// (like "#ff7b00" or "rgb(255 123 0)"), and fonts are a CSS font-family list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTheme {
+    /// The color of the page background
+    pub background: Option<String>,
+    /// The color of text
+    pub foreground: Option<String>,
+    /// The color of headings
+    pub accent: Option<String>,
+    /// The color of links
+    pub link: Option<String>,
+    /// The background color of code blocks
+    pub code_background: Option<String>,
+    /// The font of text, like "\"Inter\", sans-serif"
+    ///
+    /// Fonts that visitors might not have installed need to be loaded by `additional_css`.
+    pub font: Option<String>,
+    /// The font of code
+    pub mono_font: Option<String>,
+}
+
+impl CustomTheme {
+    /// CSS for oranda's pages, to go after oranda.css
+    ///
+    /// The `#oranda` selector makes this win over both the themes and their dark modes.
+    pub fn site_css(&self) -> Result<String> {
+        self.validate()?;
+        let mut vars = vec![];
+        let mut rules = vec![];
+        // Themes like cupcake and hacker color the body with their own variables
+        let mut body = vec![];
+        if let Some(background) = &self.background {
+            vars.push(("--bg-color", background));
+            body.push("background-color: var(--bg-color);");
+        }
+        if let Some(foreground) = &self.foreground {
+            vars.push(("--fg-color", foreground));
+            body.push("color: var(--fg-color);");
+        }
+        if !body.is_empty() {
+            rules.push(format!("html#oranda body {{ {} }}", body.join(" ")));
+        }
+        if let Some(accent) = &self.accent {
+            // Themes like axo color their headings with this
+            vars.push(("--highlight-color", accent));
+            rules.push(format!(
+                "html#oranda :is(h1, h2, h3, .title) {{ color: {accent}; }}"
+            ));
+        }
+        if let Some(link) = &self.link {
+            vars.push(("--link-color", link));
+        }
+        if let Some(code_background) = &self.code_background {
+            // Highlighted code has the syntax theme's background as an inline style
+            rules.push(format!(
+                "html#oranda pre {{ background-color: {code_background} !important; }}"
+            ));
+        }
+        if let Some(font) = &self.font {
+            vars.push(("--font-face", font));
+        }
+        if let Some(mono_font) = &self.mono_font {
+            rules.push(format!(
+                "html#oranda :is(code, pre, kbd) {{ font-family: {mono_font}; }}"
+            ));
+        }
+        Ok(generated_css("html#oranda", &vars, &rules))
+    }
+
+    /// CSS for oranda's mdbook theme, to go after the theme's variables
+    pub fn mdbook_css(&self) -> Result<String> {
+        self.validate()?;
+        let mut vars = vec![];
+        let mut rules = vec![];
+        if let Some(background) = &self.background {
+            vars.push(("--bg", background));
+        }
+        if let Some(foreground) = &self.foreground {
+            vars.push(("--fg", foreground));
+        }
+        if let Some(accent) = &self.accent {
+            vars.push(("--title-fg", accent));
+            vars.push(("--subtitle-fg", accent));
+        }
+        if let Some(link) = &self.link {
+            vars.push(("--links", link));
+            vars.push(("--links-hover", link));
+            vars.push(("--sidebar-active", link));
+        }
+        if let Some(code_background) = &self.code_background {
+            rules.push(format!(
+                ":is(.oranda-dark, .oranda-light) .hljs {{ background: {code_background}; }}"
+            ));
+        }
+        if let Some(font) = &self.font {
+            vars.push(("--main-font", font));
+        }
+        if let Some(mono_font) = &self.mono_font {
+            vars.push(("--mono-font", mono_font));
+        }
+        Ok(generated_css(
+            ":is(.oranda-dark, .oranda-light)",
+            &vars,
+            &rules,
+        ))
+    }
+
+    /// Check that none of the values can break out of the CSS they're put in
+    fn validate(&self) -> Result<()> {
+        let values = [
+            ("background", &self.background),
+            ("foreground", &self.foreground),
+            ("accent", &self.accent),
+            ("link", &self.link),
+            ("code_background", &self.code_background),
+            ("font", &self.font),
+            ("mono_font", &self.mono_font),
+        ];
+        for (field, value) in values {
+            let Some(value) = value else {
+                continue;
+            };
+            if value.trim().is_empty() || value.contains([';', '{', '}', '<', '>']) {
+                return Err(OrandaError::InvalidCustomTheme {
+                    field: field.to_owned(),
+                    value: value.to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn generated_css(selector: &str, vars: &[(&str, &String)], rules: &[String]) -> String {
+    let mut css = format!("/* Generated from styles.custom_theme */\n{selector} {{\n");
+    for (name, value) in vars {
+        css.push_str(&format!("  {name}: {value};\n"));
+    }
+    css.push_str("}\n");
+    for rule in rules {
+        css.push_str(rule);
+        css.push('\n');
+    }
+    css
+}
@@ -88,8 +88,13 @@ impl Page {
             tracing::warn!("{} could not be found on disk!", path);
         }
         let template = templates.get(template_name)?;
-        let context =
-            context!(layout => templates.layout, page => context, markdown_content => body);
+        let context = context!(
+            layout => templates.layout,
+            site => templates.site,
+            data => templates.data,
+            page => context,
+            markdown_content => body
+        );
         let contents = template.render(context)?;
         Ok(Self {
             contents,
@@ -97,6 +102,24 @@ impl Page {
         })
     }
 
+    /// Creates a new page by rendering a template file (like `releases.j2`) from the project.
+    /// It gets the same context as our own templates, so it can extend `layout.html`.
+    pub fn new_from_user_template(path: &str, templates: &Templates) -> Result<Self> {
+        let src_path = Utf8PathBuf::from_path_buf(std::env::current_dir()?)
+            .expect("Current directory is not UTF-8");
+        let Some(full_path) = determine_path(src_path, &None::<Utf8PathBuf>, path)? else {
+            return Err(OrandaError::PathDoesNotExist {
+                path: path.to_string(),
+            });
+        };
+        let source = SourceFile::load_local(full_path)?;
+        let contents = templates.render_str_to_string(path, source.contents())?;
+        let filename = source::get_filename_with_dir(path)?
+            .map(|filename| format!("{filename}.html"))
+            .unwrap_or_else(|| Self::filename(path));
+        Ok(Self { contents, filename })
+    }
+
     fn load_and_render_contents(
         source: &str,
         syntax_theme: &SyntaxTheme,
@@ -10,6 +10,18 @@ pub fn is_markdown(file: &str) -> bool {
     }
 }
 
+/// Whether a file is a jinja2 template (`.j2` or `.jinja`)
+pub fn is_template(file: &str) -> bool {
+    let file_path = Path::new(&file);
+    match file_path.extension() {
+        None => false,
+        Some(ext) => matches!(
+            ext.to_string_lossy().to_lowercase().as_str(),
+            "j2" | "jinja"
+        ),
+    }
+}
+
 pub fn get_filename_with_dir(file: &str) -> Result<Option<Utf8PathBuf>> {
     // Try diffing with the execution directory in case the user has provided an absolute-ish
     // path, in order to obtain the relative-to-dir path segment
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use crate::config::Config;
 use crate::errors::Result;
-use crate::site::changelog::ChangelogContext;
+use crate::site::changelog::{feed_releases, feed_updated, ChangelogContext, FEED_NAMESPACE};
 use crate::site::link::generate_absolute;
 use rss::extension::atom;
+use rss::extension::dublincore::DublinCoreExtensionBuilder;
+use rss::extension::ExtensionBuilder;
 use rss::{CategoryBuilder, Channel, ChannelBuilder, GuidBuilder, Item, ItemBuilder};
 
 pub fn generate_rss_feed(context: &ChangelogContext, config: &Config) -> Result<Channel> {
@@ -11,15 +15,35 @@ pub fn generate_rss_feed(context: &ChangelogContext, config: &Config) -> Result<
         .domain(config.project.repository.clone())
         .build();
 
+    let releases = feed_releases(context, config);
     let mut items: Vec<Item> = Vec::new();
-    for release in &context.releases {
+    for release in releases {
         let link =
             generate_absolute(config, &format!("changelog/{}", release.version_tag)).unwrap();
         let guid = GuidBuilder::default().permalink(true).value(&link).build();
+        // RSS wants an email address for `author`, so we use Dublin Core for names
+        let creators = if release.contributors.is_empty() {
+            vec![config.project.name.clone()]
+        } else {
+            release.contributors.clone()
+        };
+        let mut categories = vec![category.clone()];
+        categories.extend(release.sections.iter().map(|section| {
+            CategoryBuilder::default()
+                .name(section.title.clone())
+                .build()
+        }));
         let item = ItemBuilder::default()
             .title(release.name.clone().unwrap_or(release.version_tag.clone()))
+            .description(release.summary.clone())
             .content(Some(release.body.clone()))
-            .categories(vec![category.clone()])
+            .categories(categories)
+            .pub_date(release.date.map(|date| date.to_rfc2822()))
+            .dublin_core_ext(
+                DublinCoreExtensionBuilder::default()
+                    .creators(creators)
+                    .build(),
+            )
             .link(link)
             .guid(guid)
             .build();
@@ -34,7 +58,18 @@ pub fn generate_rss_feed(context: &ChangelogContext, config: &Config) -> Result<
     let atom_link = atom::AtomExtensionBuilder::default()
         .links(vec![self_link])
         .build();
+    let (prefix, namespace) = FEED_NAMESPACE;
+    let page_count = ExtensionBuilder::default()
+        .name(format!("{prefix}:pageCount"))
+        .value(Some(context.page_count.to_string()))
+        .build();
+    let extensions = BTreeMap::from([(
+        prefix.to_owned(),
+        BTreeMap::from([("pageCount".to_owned(), vec![page_count])]),
+    )]);
     let channel = ChannelBuilder::default()
+        .namespaces(BTreeMap::from([(prefix.to_owned(), namespace.to_owned())]))
+        .extensions(extensions)
         .title(format!("{} Changelog", &config.project.name))
         .description(format!(
             "Changelog information for {}",
@@ -42,6 +77,8 @@ pub fn generate_rss_feed(context: &ChangelogContext, config: &Config) -> Result<
         ))
         .categories(vec![category])
         .items(items)
+        .pub_date(feed_updated(releases).map(|date| date.to_rfc2822()))
+        .last_build_date(Some(chrono::Utc::now().to_rfc2822()))
         .link(generate_absolute(config, "changelog").unwrap())
         .atom_ext(atom_link)
         .build();
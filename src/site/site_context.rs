@@ -0,0 +1,110 @@
+//! The `site` object every template gets, with everything we know about the project.
+//!
+//! Page templates only get what their own context (under `page`) has in it, so this is what
+//! lets a template show, say, the last few releases on a page that isn't the changelog.
+//! It's built (and turned into a template value) once per site and never changed by rendering.
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::data::funding::Funding;
+use crate::data::Context;
+use crate::errors::*;
+use crate::site::artifacts::{self, ArtifactsContext};
+use crate::site::funding::{self, FundingContext};
+
+#[derive(Serialize, Debug, Default)]
+pub struct SiteContext {
+    pub project: SiteProject,
+    /// All of the releases, from newest to oldest
+    pub releases: Vec<SiteRelease>,
+    /// Index into `releases` for the "best" latest release (preferring stable releases)
+    pub latest_release: Option<usize>,
+    /// Index into `releases` for the newest prerelease, if there is one
+    pub latest_prerelease: Option<usize>,
+    pub has_prereleases: bool,
+    /// The same as the funding page's context, if funding is enabled
+    pub funding: Option<FundingContext>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct SiteProject {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub license: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SiteRelease {
+    pub version_tag: String,
+    pub name: Option<String>,
+    pub formatted_date: Option<String>,
+    pub date: Option<DateTime<FixedOffset>>,
+    pub is_prerelease: bool,
+    /// The release notes, as markdown
+    pub body: Option<String>,
+    /// The same as the install page's context, if artifacts are enabled
+    pub artifacts: Option<ArtifactsContext>,
+}
+
+impl SiteContext {
+    pub fn new(
+        config: &Config,
+        context: Option<&Context>,
+        funding: Option<&Funding>,
+    ) -> Result<Self> {
+        let project = SiteProject {
+            name: config.project.name.clone(),
+            version: config.project.version.clone(),
+            description: config.project.description.clone(),
+            homepage: config.project.homepage.clone(),
+            repository: config.project.repository.clone(),
+            license: config.project.license.clone(),
+        };
+        let funding = match (&config.components.funding, funding) {
+            (Some(funding_cfg), Some(funding)) => Some(funding::context(funding_cfg, funding)?),
+            _ => None,
+        };
+        let Some(context) = context else {
+            return Ok(Self {
+                project,
+                funding,
+                ..Default::default()
+            });
+        };
+
+        let latest_prerelease = context
+            .releases
+            .iter()
+            .position(|release| release.source.is_prerelease());
+        let mut releases = Vec::new();
+        for release in &context.releases {
+            let artifacts = if config.components.artifacts_enabled() {
+                Some(artifacts::release_context(context, release, config)?)
+            } else {
+                None
+            };
+            releases.push(SiteRelease {
+                version_tag: release.source.version_tag().to_owned(),
+                name: release.source.name().map(|name| name.to_owned()),
+                formatted_date: release.source.formatted_date(),
+                date: release.source.parsed_date(),
+                is_prerelease: release.source.is_prerelease(),
+                body: release.source.body().map(|body| body.to_owned()),
+                artifacts,
+            });
+        }
+        Ok(Self {
+            project,
+            latest_prerelease,
+            releases,
+            latest_release: context.latest_release,
+            has_prereleases: context.has_prereleases,
+            funding,
+        })
+    }
+}
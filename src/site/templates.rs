@@ -6,11 +6,13 @@
 //! can also use features such as imports, inheritance, extends, and so on.
 
 use crate::config::Config;
+use crate::data::funding::Funding;
 use crate::data::Context;
 use crate::errors::Result;
 use crate::site::layout::LayoutContext;
 use crate::site::markdown::SyntaxTheme;
-use crate::site::{link, markdown};
+use crate::site::site_context::SiteContext;
+use crate::site::{data_files, link, markdown};
 use include_dir::{include_dir, Dir};
 use minijinja::value::Value;
 use minijinja::{context, AutoEscape, Environment, Template};
@@ -24,10 +26,20 @@ pub struct Templates<'a> {
     pub env: Environment<'a>,
     /// Layout context used for every render
     pub layout: LayoutContext,
+    /// Everything about the project, for every render (see [`SiteContext`][])
+    ///
+    /// This is converted to a template value up front, so it isn't serialized again for every page.
+    pub site: Value,
+    /// The files in `build.data_dir`, for every render (see [`data_files`][])
+    pub data: Value,
 }
 
 impl<'a> Templates<'a> {
-    pub fn new(config: &Config, context: Option<&Context>) -> Result<Self> {
+    pub fn new(
+        config: &Config,
+        context: Option<&Context>,
+        funding: Option<&Funding>,
+    ) -> Result<Self> {
         let mut env = Environment::new();
         let mut files = HashMap::new();
         // These two `expects` should never happen in production, because all of these things are
@@ -45,7 +57,14 @@ impl<'a> Templates<'a> {
         // Use opt-in autoescape
         env.set_auto_escape_callback(|_| AutoEscape::None);
         let layout = LayoutContext::new(config, context)?;
-        Ok(Self { env, layout })
+        let site = Value::from_serializable(&SiteContext::new(config, context, funding)?);
+        let data = Value::from_serializable(&data_files::load_for_build(&config.build)?);
+        Ok(Self {
+            env,
+            layout,
+            site,
+            data,
+        })
     }
 
     pub fn new_for_workspace_index(workspace_config: &Config) -> Result<Self> {
@@ -61,7 +80,14 @@ impl<'a> Templates<'a> {
         }
         env.add_filter("generate_link", Self::generate_link);
         let layout = LayoutContext::new_for_workspace_index(workspace_config)?;
-        Ok(Self { env, layout })
+        let site = Value::from_serializable(&SiteContext::new(workspace_config, None, None)?);
+        let data = Value::from_serializable(&data_files::load_for_build(&workspace_config.build)?);
+        Ok(Self {
+            env,
+            layout,
+            site,
+            data,
+        })
     }
 
     pub fn get(&self, name: &str) -> Result<Template> {
@@ -69,11 +95,30 @@ impl<'a> Templates<'a> {
     }
 
     pub fn render_to_string(&self, name: &str, context: Value) -> Result<String> {
-        let context_with_layout = context!(layout => self.layout, page => context);
+        let context_with_layout = context!(
+            layout => self.layout,
+            site => self.site,
+            data => self.data,
+            page => context
+        );
         let template = self.env.get_template(name)?;
         Ok(template.render(context_with_layout)?)
     }
 
+    /// Render a template that isn't built into oranda (like an additional page), which can
+    /// extend or include any of ours
+    pub fn render_str_to_string(&self, name: &str, source: &str) -> Result<String> {
+        let context_with_layout = context!(
+            layout => self.layout,
+            site => self.site,
+            data => self.data,
+            page => ()
+        );
+        Ok(self
+            .env
+            .render_named_str(name, source, context_with_layout)?)
+    }
+
     fn load_files(dir: &Dir, files: &mut HashMap<String, String>) -> Result<()> {
         for entry in dir.entries() {
             if let Some(file) = entry.as_file() {
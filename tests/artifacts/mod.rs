@@ -0,0 +1,504 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use oranda::config::{
+    ArtifactSystem, ArtifactsConfig, Config, LocalReleasesConfig, PackageManager, ProjectConfig,
+};
+use oranda::data::artifacts::{
+    File, InstallMethod, InstallerIdx, InstallerPreference, ReleaseArtifacts, SignatureScheme,
+};
+use oranda::data::cargo_dist::DistManifest;
+use oranda::data::Context;
+use oranda::site::artifacts;
+
+use crate::utils::tokio_utils::TEST_RUNTIME;
+
+fn artifacts_from_names(names: &[&str]) -> ReleaseArtifacts {
+    artifacts_from_names_for_apps(names, vec![])
+}
+
+fn artifacts_from_names_for_apps(names: &[&str], apps: Vec<String>) -> ReleaseArtifacts {
+    let mut artifacts = ReleaseArtifacts::new(apps);
+    for name in names {
+        artifacts.add_file(File {
+            name: name.to_string(),
+            download_url: format!("https://example.com/{name}"),
+            view_path: None,
+            checksum_file: None,
+            signature: None,
+            local_path: None,
+            infer: true,
+        });
+    }
+    artifacts.add_signatures();
+    artifacts.add_inference();
+    artifacts.select_installers(&ArtifactsConfig::default());
+    artifacts
+}
+
+/// Get the file names of the downloads recommended for a target, in order
+fn downloads_for_target(artifacts: &ReleaseArtifacts, target: &str) -> Vec<String> {
+    let Some(installers) = artifacts.installers_by_target().get(target) else {
+        return vec![];
+    };
+    installers
+        .iter()
+        .filter_map(|idx| match artifacts.installer(*idx).method {
+            InstallMethod::Download { file } => Some(artifacts.file(file).name.clone()),
+            InstallMethod::Run { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn it_infers_new_bundle_formats() {
+    let artifacts = artifacts_from_names(&[
+        "axo.AppImage",
+        "axo-setup.exe",
+        "axo.exe",
+        "axo.pkg",
+        "axo.msix",
+        "axo.apk",
+    ]);
+
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo.AppImage"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-pc-windows-msvc"),
+        vec!["axo-setup.exe", "axo.msix"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "aarch64-apple-darwin"),
+        vec!["axo.pkg"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "aarch64-linux-android"),
+        vec!["axo.apk"]
+    );
+}
+
+#[test]
+fn it_infers_bundle_formats_regardless_of_case() {
+    let artifacts = artifacts_from_names(&["Axo-Setup.EXE", "axo.MSI", "Axo.DMG", "axo.appimage"]);
+
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-pc-windows-msvc"),
+        vec!["Axo-Setup.EXE", "axo.MSI"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "aarch64-apple-darwin"),
+        vec!["Axo.DMG"]
+    );
+    assert!(downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu")
+        .contains(&"axo.appimage".to_owned()));
+}
+
+#[test]
+fn it_only_treats_exes_named_like_installers_as_setup_programs() {
+    let artifacts = artifacts_from_names(&[
+        "cargo-binstall-x86_64-pc-windows-msvc.exe",
+        "uninstall.exe",
+        "axo_installer.exe",
+        "setup.exe",
+    ]);
+
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-pc-windows-msvc"),
+        vec!["axo_installer.exe", "setup.exe"]
+    );
+}
+
+#[test]
+fn it_classifies_uppercase_packages_like_lowercase_ones() {
+    let artifacts =
+        artifacts_from_names(&["AXO_0.1.0_AMD64.DEB", "AXO-0.1.0-PY3-NONE-WIN_AMD64.WHL"]);
+
+    let installer = |label: &str| {
+        artifacts
+            .installers()
+            .map(|(_, installer)| installer)
+            .find(|installer| installer.label == label)
+            .unwrap()
+    };
+    assert_eq!(installer("DEB").systems, ArtifactSystem::DEB_DISTROS);
+    // Wheels are packages for pip, not native installers
+    assert_eq!(
+        installer("wheel").targets.values().next(),
+        Some(&InstallerPreference::Custom)
+    );
+}
+
+#[test]
+fn it_infers_targets_from_wheel_tags() {
+    let artifacts = artifacts_from_names(&[
+        "axo-0.1.0-py3-none-manylinux_2_17_x86_64.manylinux2014_x86_64.whl",
+        "axo-0.1.0-py3-none-macosx_11_0_arm64.whl",
+        "axo-0.1.0-py3-none-win_amd64.whl",
+    ]);
+
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo-0.1.0-py3-none-manylinux_2_17_x86_64.manylinux2014_x86_64.whl"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "aarch64-apple-darwin"),
+        vec!["axo-0.1.0-py3-none-macosx_11_0_arm64.whl"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-pc-windows-msvc"),
+        vec!["axo-0.1.0-py3-none-win_amd64.whl"]
+    );
+    assert!(downloads_for_target(&artifacts, "x86_64-apple-darwin").is_empty());
+}
+
+#[test]
+fn it_infers_targets_from_go_style_names() {
+    let artifacts = artifacts_from_names(&[
+        "axo_0.1.0_linux_amd64.tar.gz",
+        "axo_0.1.0_darwin_arm64.tar.gz",
+        "axo_0.1.0_windows_386.zip",
+    ]);
+
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo_0.1.0_linux_amd64.tar.gz"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "aarch64-apple-darwin"),
+        vec!["axo_0.1.0_darwin_arm64.tar.gz"]
+    );
+    assert_eq!(
+        downloads_for_target(&artifacts, "i686-pc-windows-msvc"),
+        vec!["axo_0.1.0_windows_386.zip"]
+    );
+}
+
+#[test]
+fn it_tags_linux_packages_with_distros() {
+    let artifacts = artifacts_from_names(&["axo_0.1.0_amd64.deb", "axo-0.1.0.x86_64.rpm"]);
+
+    for (_, installer) in artifacts.installers() {
+        let expected = if installer.label == "deb" {
+            ArtifactSystem::DEB_DISTROS
+        } else {
+            ArtifactSystem::RPM_DISTROS
+        };
+        assert_eq!(installer.systems, expected);
+    }
+}
+
+#[test]
+fn it_puts_native_packages_first_for_each_distro() {
+    let _guard = TEST_RUNTIME.enter();
+    let dir = TempDir::new().unwrap();
+    for name in [
+        "axo_0.1.0_amd64.deb",
+        "axo-0.1.0.x86_64.rpm",
+        "axo-x86_64-unknown-linux-gnu.tar.gz",
+    ] {
+        dir.child(format!("v0.1.0/{name}")).write_str("").unwrap();
+    }
+    let local_config = LocalReleasesConfig {
+        paths: vec![dir.path().to_str().unwrap().to_owned()],
+        download_url: Some("https://example.com/{version}/{file}".to_owned()),
+    };
+    let artifacts_config = ArtifactsConfig {
+        auto: true,
+        ..ArtifactsConfig::default()
+    };
+    let context = Context::new_local(
+        &local_config,
+        None,
+        &ProjectConfig::default(),
+        Some(&artifacts_config),
+    )
+    .unwrap();
+    let release = context.latest().unwrap();
+    let release_context =
+        artifacts::release_context(&context, release, &Config::default()).unwrap();
+    let json = serde_json::to_value(release_context).unwrap();
+
+    let labels_for_platform = |id: &str| -> Vec<String> {
+        let platform = json["platforms_with_downloads"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|platform| platform["id"] == id)
+            .unwrap_or_else(|| panic!("no platform {id}"));
+        platform["installers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|idx| {
+                let idx = InstallerIdx(idx.as_u64().unwrap() as usize);
+                release.artifacts.installer(idx).label.clone()
+            })
+            .collect()
+    };
+    let target = "x86_64-unknown-linux-gnu";
+    assert_eq!(labels_for_platform(target), vec!["deb", "rpm", "tarball"]);
+    assert_eq!(
+        labels_for_platform(&format!("{target}-linuxubuntu")),
+        vec!["deb", "rpm", "tarball"]
+    );
+    assert_eq!(
+        labels_for_platform(&format!("{target}-linuxfedora")),
+        vec!["rpm", "deb", "tarball"]
+    );
+    // Every distro either package is native to gets an entry, after the generic ones
+    let ids = json["platforms_with_downloads"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|platform| platform["id"].as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    for system in ArtifactSystem::DEB_DISTROS
+        .iter()
+        .chain(ArtifactSystem::RPM_DISTROS)
+    {
+        let id = format!("{target}-{}", system.id());
+        assert!(ids.contains(&id), "no platform {id}");
+        assert!(ids.iter().position(|i| i == &id) > ids.iter().position(|i| i == target));
+    }
+}
+
+#[test]
+fn it_scopes_package_managers_to_targets() {
+    let mut config = ArtifactsConfig::default();
+    config.package_managers.preferred.insert(
+        "homebrew".to_owned(),
+        PackageManager {
+            targets: vec!["mac".to_owned()],
+            ..PackageManager::new("brew install axo".to_owned())
+        },
+    );
+    config.package_managers.preferred.insert(
+        "scoop".to_owned(),
+        PackageManager {
+            targets: vec!["x86_64-pc-windows-msvc".to_owned()],
+            ..PackageManager::new("scoop install axo".to_owned())
+        },
+    );
+    config.package_managers.preferred.insert(
+        "winget".to_owned(),
+        PackageManager {
+            targets: vec!["windowsarm".to_owned()],
+            ..PackageManager::new("winget install axo".to_owned())
+        },
+    );
+    config.package_managers.preferred.insert(
+        "emerge".to_owned(),
+        PackageManager {
+            targets: vec!["linuxgentoo".to_owned()],
+            ..PackageManager::new("emerge axo".to_owned())
+        },
+    );
+    config.package_managers.preferred.insert(
+        "pkg".to_owned(),
+        PackageManager {
+            targets: vec!["freebsd".to_owned()],
+            ..PackageManager::new("pkg install axo".to_owned())
+        },
+    );
+    let mut artifacts = ReleaseArtifacts::new(vec![]);
+    artifacts.add_package_managers(&config);
+    artifacts.select_installers(&config);
+
+    let labels_for_target = |target: &str| -> Vec<String> {
+        artifacts.installers_by_target()[target]
+            .iter()
+            .map(|idx| artifacts.installer(*idx).label.clone())
+            .collect()
+    };
+    assert_eq!(labels_for_target("aarch64-apple-darwin"), vec!["homebrew"]);
+    assert_eq!(labels_for_target("x86_64-pc-windows-msvc"), vec!["scoop"]);
+    // Every system id works, not just the OS families and deb/rpm distros
+    assert_eq!(labels_for_target("aarch64-pc-windows-msvc"), vec!["winget"]);
+    assert_eq!(
+        labels_for_target("x86_64-unknown-linux-gnu"),
+        vec!["emerge"]
+    );
+    assert_eq!(labels_for_target("x86_64-unknown-freebsd"), vec!["pkg"]);
+    let emerge = artifacts
+        .installers()
+        .map(|(_, installer)| installer)
+        .find(|installer| installer.label == "emerge")
+        .unwrap();
+    assert_eq!(emerge.systems, vec![ArtifactSystem::LinuxGentoo]);
+}
+
+#[test]
+fn it_filters_files_to_the_configured_apps() {
+    let names = &[
+        "axo-x86_64-unknown-linux-gnu.tar.gz",
+        "lotl-x86_64-unknown-linux-gnu.tar.gz",
+    ];
+    let artifacts = artifacts_from_names_for_apps(names, vec!["lotl".to_owned()]);
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["lotl-x86_64-unknown-linux-gnu.tar.gz"]
+    );
+
+    // If nothing mentions the app, don't filter anything
+    let artifacts = artifacts_from_names_for_apps(names, vec!["other".to_owned()]);
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu").len(),
+        2
+    );
+}
+
+#[test]
+fn it_matches_app_names_as_whole_words() {
+    let names = &[
+        "axo-x86_64-unknown-linux-gnu.tar.gz",
+        "axolotlsay-x86_64-unknown-linux-gnu.tar.gz",
+        "install.sh",
+    ];
+    let artifacts = artifacts_from_names_for_apps(names, vec!["axo".to_owned()]);
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo-x86_64-unknown-linux-gnu.tar.gz"]
+    );
+    // Files that aren't for any particular app stick around
+    assert_eq!(
+        artifacts.installers_by_target()["x86_64-unknown-linux-gnu"].len(),
+        2
+    );
+
+    // Without explicit apps, nothing is filtered
+    let artifacts = artifacts_from_names(names);
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu").len(),
+        2
+    );
+}
+
+#[test]
+fn it_pairs_signatures_with_their_files() {
+    let artifacts = artifacts_from_names(&[
+        "axo-x86_64-unknown-linux-gnu.tar.gz",
+        "axo-x86_64-unknown-linux-gnu.tar.gz.minisig",
+        "axo-x86_64-pc-windows-msvc.zip",
+        "axo-x86_64-pc-windows-msvc.zip.sigstore.json",
+        "axo-aarch64-apple-darwin.tar.gz",
+        "axo-aarch64-apple-darwin.tar.gz.bundle",
+    ]);
+
+    let signature_of = |name: &str| {
+        let file = artifacts.files().find(|f| f.name == name).unwrap();
+        let signature = file.signature.unwrap();
+        (
+            artifacts.file(signature.file).name.clone(),
+            signature.scheme,
+        )
+    };
+    assert_eq!(
+        signature_of("axo-x86_64-unknown-linux-gnu.tar.gz"),
+        (
+            "axo-x86_64-unknown-linux-gnu.tar.gz.minisig".to_owned(),
+            SignatureScheme::Minisign
+        )
+    );
+    assert_eq!(
+        signature_of("axo-x86_64-pc-windows-msvc.zip"),
+        (
+            "axo-x86_64-pc-windows-msvc.zip.sigstore.json".to_owned(),
+            SignatureScheme::Cosign
+        )
+    );
+    // A bare `.bundle` could be anything, so it isn't taken as a signature
+    assert!(artifacts
+        .files()
+        .find(|f| f.name == "axo-aarch64-apple-darwin.tar.gz")
+        .unwrap()
+        .signature
+        .is_none());
+    // The signatures themselves shouldn't be recommended as downloads
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo-x86_64-unknown-linux-gnu.tar.gz"]
+    );
+}
+
+#[test]
+fn it_sorts_cargo_dist_extras_by_kind() {
+    let manifest: DistManifest = serde_json::from_value(serde_json::json!({
+        "releases": [{
+            "app_name": "axo",
+            "app_version": "1.0.0",
+            "artifacts": [
+                "axo-x86_64-unknown-linux-gnu.tar.gz",
+                "axo-x86_64-unknown-linux-gnu.tar.gz.sha256",
+                "axo-x86_64-unknown-linux-gnu.dwp",
+                "source.tar.gz"
+            ]
+        }],
+        "artifacts": {
+            "axo-x86_64-unknown-linux-gnu.tar.gz": {
+                "name": "axo-x86_64-unknown-linux-gnu.tar.gz",
+                "kind": "executable-zip",
+                "target_triples": ["x86_64-unknown-linux-gnu"]
+            },
+            "axo-x86_64-unknown-linux-gnu.tar.gz.sha256": {
+                "name": "axo-x86_64-unknown-linux-gnu.tar.gz.sha256",
+                "kind": "checksum"
+            },
+            "axo-x86_64-unknown-linux-gnu.dwp": {
+                "name": "axo-x86_64-unknown-linux-gnu.dwp",
+                "kind": "symbols",
+                "target_triples": ["x86_64-unknown-linux-gnu"]
+            },
+            "source.tar.gz": {
+                "name": "source.tar.gz",
+                "kind": "source-tarball"
+            }
+        }
+    }))
+    .unwrap();
+
+    let mut artifacts = ReleaseArtifacts::new(vec![]);
+    for artifact in manifest.artifacts.values() {
+        let name = artifact.name.clone().unwrap();
+        artifacts.add_file(File {
+            download_url: format!("https://example.com/{name}"),
+            name,
+            view_path: None,
+            checksum_file: None,
+            signature: None,
+            local_path: None,
+            infer: true,
+        });
+    }
+    artifacts.add_cargo_dist(&manifest);
+    artifacts.add_inference();
+    artifacts.select_installers(&ArtifactsConfig::default());
+
+    let tarball = artifacts
+        .files()
+        .find(|f| f.name == "axo-x86_64-unknown-linux-gnu.tar.gz")
+        .unwrap();
+    assert_eq!(
+        artifacts.file(tarball.checksum_file.unwrap()).name,
+        "axo-x86_64-unknown-linux-gnu.tar.gz.sha256"
+    );
+    let names = |files: Vec<(_, &File)>| {
+        files
+            .into_iter()
+            .map(|(_, f)| f.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(artifacts.source_tarballs().collect()),
+        vec!["source.tar.gz"]
+    );
+    assert_eq!(
+        names(artifacts.developer_files().collect()),
+        vec!["axo-x86_64-unknown-linux-gnu.dwp"]
+    );
+    // None of the extras should be recommended as an installer
+    assert_eq!(
+        downloads_for_target(&artifacts, "x86_64-unknown-linux-gnu"),
+        vec!["axo-x86_64-unknown-linux-gnu.tar.gz"]
+    );
+}
@@ -40,6 +40,12 @@ fn it_loads_a_js_project_config() {
     assert_eq!(project.name, Some("axo".to_owned()));
     assert_eq!(project.description, Some(">o_o<".to_owned()));
     assert_eq!(project.homepage, None);
+    let package_managers = config.package_managers.unwrap();
+    assert_eq!(
+        package_managers.preferred["npm"].command,
+        "npm install -g axo"
+    );
+    assert_eq!(package_managers.additional["npx"].command, "npx axo");
     tempdir
         .close()
         .expect("could not successfully delete temporary directory");
@@ -82,6 +88,12 @@ fn it_loads_a_rust_project_config() {
     assert_eq!(project.name, Some("axo".to_owned()));
     assert_eq!(project.description, Some("blublublub".to_owned()));
     assert_eq!(project.version, Some("0.0.0".to_string()));
+    let package_managers = config.package_managers.unwrap();
+    assert_eq!(
+        package_managers.preferred["cargo"].command,
+        "cargo install axo --locked"
+    );
+    assert!(!package_managers.has("binstall"));
     tempdir
         .close()
         .expect("could not successfully delete temporary directory");
@@ -0,0 +1,119 @@
+use assert_fs::TempDir;
+use camino::Utf8PathBuf;
+use oranda::errors::OrandaError;
+use oranda::http_cache::HttpCache;
+use std::time::Duration;
+
+use crate::utils::stand_in::{response, StandIn};
+use crate::utils::tokio_utils::TEST_RUNTIME;
+
+fn cache_in(dir: &TempDir, ttl: Duration, offline: bool) -> HttpCache {
+    HttpCache {
+        dir: Utf8PathBuf::from_path_buf(dir.path().to_owned()).unwrap(),
+        ttl,
+        offline,
+    }
+}
+
+#[test]
+fn it_reuses_fresh_responses() {
+    let dir = TempDir::new().unwrap();
+    let stand_in = StandIn::serve(vec![response("200 OK", &[], "hello")]);
+    let url = format!("{}/releases", stand_in.url);
+    let cache = cache_in(&dir, Duration::from_secs(3600), false);
+
+    let first = TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+    let second = TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+
+    assert_eq!(first.text(), "hello");
+    assert_eq!(second.text(), "hello");
+    assert_eq!(stand_in.requests().len(), 1);
+}
+
+#[test]
+fn it_revalidates_stale_responses_with_their_etag() {
+    let dir = TempDir::new().unwrap();
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[("etag", "\"v1\"")], "hello"),
+        response("304 Not Modified", &[], ""),
+    ]);
+    let url = format!("{}/releases", stand_in.url);
+    let cache = cache_in(&dir, Duration::ZERO, false);
+
+    TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+    let revalidated = TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+
+    assert_eq!(revalidated.text(), "hello");
+    let requests = stand_in.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+}
+
+#[test]
+fn it_only_uses_the_cache_when_offline() {
+    let dir = TempDir::new().unwrap();
+    let stand_in = StandIn::serve(vec![response("200 OK", &[], "hello")]);
+    let url = format!("{}/releases", stand_in.url);
+
+    let offline = cache_in(&dir, Duration::ZERO, true);
+    let missed = TEST_RUNTIME.block_on(offline.get(&url));
+    assert!(matches!(missed, Err(OrandaError::OfflineCacheMiss { .. })));
+
+    TEST_RUNTIME
+        .block_on(cache_in(&dir, Duration::ZERO, false).get(&url))
+        .unwrap();
+    let cached = TEST_RUNTIME.block_on(offline.get(&url)).unwrap();
+    assert_eq!(cached.text(), "hello");
+    assert_eq!(stand_in.requests().len(), 1);
+}
+
+#[test]
+fn it_keeps_responses_for_different_credentials_apart() {
+    let dir = TempDir::new().unwrap();
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], "private"),
+        response("200 OK", &[], "public"),
+    ]);
+    let url = format!("{}/releases", stand_in.url);
+    let cache = cache_in(&dir, Duration::from_secs(3600), false);
+    let send = |request: reqwest::RequestBuilder| async move {
+        Ok(request.send().await?.error_for_status()?)
+    };
+
+    let with_token = reqwest::Client::new()
+        .get(&url)
+        .header("Authorization", "token hunter2");
+    let private = TEST_RUNTIME
+        .block_on(cache.fetch(with_token, send))
+        .unwrap();
+    let public = TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+
+    assert_eq!(private.text(), "private");
+    assert_eq!(public.text(), "public");
+    assert_eq!(stand_in.requests().len(), 2);
+    // The token itself never ends up on disk
+    for entry in std::fs::read_dir(dir.path()).unwrap() {
+        let contents = std::fs::read(entry.unwrap().path()).unwrap();
+        assert!(!String::from_utf8_lossy(&contents).contains("hunter2"));
+    }
+}
+
+#[test]
+fn it_only_falls_back_to_stale_responses_when_the_server_is_unavailable() {
+    let dir = TempDir::new().unwrap();
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], "hello"),
+        response("404 Not Found", &[], ""),
+        response("503 Service Unavailable", &[], ""),
+    ]);
+    let url = format!("{}/releases", stand_in.url);
+    let cache = cache_in(&dir, Duration::ZERO, false);
+
+    TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+    // A deleted release (or revoked token) is an error, not a reason to use old data
+    assert!(TEST_RUNTIME.block_on(cache.get(&url)).is_err());
+    // But a server having a bad day is
+    let stale = TEST_RUNTIME.block_on(cache.get(&url)).unwrap();
+    assert_eq!(stale.text(), "hello");
+    assert_eq!(stand_in.requests().len(), 3);
+}
@@ -1,4 +1,4 @@
-use assert_fs::prelude::PathChild;
+use assert_fs::prelude::{FileWriteStr, PathChild};
 use assert_fs::TempDir;
 use scraper::{Html, Selector};
 use serde_json::json;
@@ -6,10 +6,15 @@ use serde_json::json;
 mod fixtures;
 
 use super::utils::tokio_utils::TEST_RUNTIME;
+use camino::Utf8Path;
 use fixtures::oranda_config;
 use oranda::config::style::ORANDA_CSS_TAG;
+use oranda::data::git::GitRelease;
+use oranda::data::{Release, ReleaseSource};
+use oranda::errors::OrandaError;
 use oranda::site::page::Page;
 use oranda::site::Site;
+use oranda::site::{changelog, data_files};
 
 fn temp_build_dir() -> TempDir {
     TempDir::new().unwrap()
@@ -156,6 +161,113 @@ fn reads_theme() {
     assert_selector_exists(&page.contents, "html.cupcake");
 }
 
+#[test]
+fn it_pairs_themes_with_their_twin_in_auto_mode() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let config = oranda_config::from_json(
+        json!({
+            "styles": {
+                "theme": "axo_light",
+                "color_scheme": "auto"
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "index.html");
+    // Without JS, pages still get the theme they picked
+    assert_selector_exists(
+        &page.contents,
+        "html.axo[data-light-theme='axo'][data-dark-theme='dark axo']",
+    );
+    assert_selector_exists(
+        &page.contents,
+        "html[data-theme-label='Axo Light / Axo Dark']",
+    );
+    assert_selector_exists(&page.contents, "header button.theme-toggle");
+
+    // Themes without a twin don't get a toggle
+    let config = oranda_config::from_json(
+        json!({
+            "styles": {
+                "theme": "hacker",
+                "color_scheme": "auto"
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "index.html");
+    let doc = Html::parse_document(&page.contents);
+    for selector in ["html[data-dark-theme]", ".theme-toggle"] {
+        assert!(doc
+            .select(&Selector::parse(selector).unwrap())
+            .next()
+            .is_none());
+    }
+}
+
+#[test]
+fn it_layers_a_custom_theme_over_the_base_theme() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let config = oranda_config::from_json(
+        json!({
+            "styles": {
+                "theme": "light",
+                "custom_theme": {
+                    "background": "#fffaf0",
+                    "link": "rgb(255 123 0)",
+                    "mono_font": "\"JetBrains Mono\", monospace"
+                }
+            },
+            "components": {
+                "mdbook": false
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "index.html");
+    assert_selector_exists(&page.contents, "html.light");
+    assert_selector_exists(&page.contents, "link[rel='stylesheet'][href='/theme.css']");
+    site.write(Some(&config)).unwrap();
+    let css =
+        std::fs::read_to_string(Utf8Path::new(&config.build.dist_dir).join("theme.css")).unwrap();
+    assert!(css.contains("--bg-color: #fffaf0;"));
+    assert!(css.contains("--link-color: rgb(255 123 0);"));
+    assert!(css.contains("font-family: \"JetBrains Mono\", monospace;"));
+    assert!(!css.contains("--fg-color:"));
+    // Themes that color the body themselves still get the custom colors
+    assert!(css.contains("html#oranda body { background-color: var(--bg-color); }"));
+
+    // The mdbook gets the same colors, under its own names
+    let palette = config.styles.custom_theme.as_ref().unwrap();
+    let css = palette.mdbook_css().unwrap();
+    assert!(css.contains("--bg: #fffaf0;"));
+    assert!(css.contains("--links: rgb(255 123 0);"));
+    assert!(css.contains("--mono-font: \"JetBrains Mono\", monospace;"));
+
+    let config = oranda_config::from_json(
+        json!({
+            "styles": {
+                "custom_theme": { "background": "red; } body { display: none" }
+            },
+            "components": {
+                "mdbook": false
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let err = site.write(Some(&config)).unwrap_err();
+    assert!(matches!(
+        err,
+        OrandaError::InvalidCustomTheme { ref field, .. } if field == "background"
+    ));
+}
+
 #[test]
 fn creates_footer() {
     let _guard = TEST_RUNTIME.enter();
@@ -227,3 +339,654 @@ fn it_inserts_plausible_tag() {
     let page = find_page(&site.pages, "index.html");
     assert_selector_exists(&page.contents, "script[defer='true'][data-domain='axodotdev.github.io'][src='https://plausible.io/js/script.js']");
 }
+
+#[test]
+fn it_renders_template_pages_with_site_data() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    for tag in ["v1.0.0", "v1.1.0-beta.1", "v1.1.0"] {
+        project_dir
+            .child(format!(
+                "releases/{tag}/axo-x86_64-unknown-linux-gnu.tar.gz"
+            ))
+            .write_str("")
+            .unwrap();
+    }
+    let releases_page = project_dir.child("releases.j2");
+    releases_page
+        .write_str(
+            "{% extends \"layout.html\" %}{% block content %}<ul id=\"releases\">\
+             {% for release in site.releases[:2] %}<li>{{ release.version_tag }}\
+             {% if loop.index0 == site.latest_release %} (latest){% endif %} \
+             {{ release.artifacts.downloadable_files | length }}</li>{% endfor %}</ul>\
+             <p id=\"prerelease\">{{ site.releases[site.latest_prerelease].version_tag }}</p>\
+             <p id=\"oldest\">{{ site.releases[2].artifacts.downloadable_files | length }}</p>\
+             {% endblock %}",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "build": {
+                "additional_pages": {
+                    "Releases": releases_page.path().display().to_string()
+                }
+            },
+            "components": {
+                "source": "local",
+                "local_releases": {
+                    "paths": [project_dir.child("releases").path().display().to_string()],
+                    "download_url": "https://example.com/{version}/{file}"
+                },
+                "artifacts": {
+                    "auto": true
+                },
+                "changelog": false,
+                "funding": false
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = site
+        .pages
+        .iter()
+        .find(|page| page.filename.ends_with("releases.html"))
+        .unwrap();
+    assert_eq!(
+        selector_get_inner(&page.contents, "#releases"),
+        "<li>v1.1.0 (latest) 1</li><li>v1.1.0-beta.1 1</li>"
+    );
+    assert_eq!(
+        selector_get_inner(&page.contents, "#prerelease"),
+        "v1.1.0-beta.1"
+    );
+    // Older releases get their artifacts too
+    assert_eq!(selector_get_inner(&page.contents, "#oldest"), "1");
+    // It's in the nav like any other additional page
+    assert_selector_exists(&page.contents, "nav.nav a[href$='releases/']");
+}
+
+#[test]
+fn it_exposes_data_files_to_templates() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let data_dir = project_dir.child("data");
+    data_dir
+        .child("features.json")
+        .write_str(r#"[{"name": "Fast"}, {"name": "Small"}]"#)
+        .unwrap();
+    data_dir
+        .child("team/members.yml")
+        .write_str("- name: Ferris\n  role: Mascot\n")
+        .unwrap();
+    data_dir
+        .child("platforms.toml")
+        .write_str("linux = true\nwindows = false\n")
+        .unwrap();
+    data_dir.child("notes.txt").write_str("ignored").unwrap();
+    let page_path = project_dir.child("about.j2");
+    page_path
+        .write_str(
+            "{% extends \"layout.html\" %}{% block content %}\
+             <ul id=\"features\">{% for feature in data.features %}<li>{{ feature.name }}</li>{% endfor %}</ul>\
+             <p id=\"team\">{{ data.team.members[0].name }}, {{ data.team.members[0].role }}</p>\
+             <p id=\"platforms\">{{ data.platforms.linux }} {{ data.platforms.windows }}</p>\
+             {% endblock %}",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "build": {
+                "data_dir": data_dir.path().display().to_string(),
+                "additional_pages": {
+                    "About": page_path.path().display().to_string()
+                }
+            },
+            "components": {
+                "artifacts": false,
+                "changelog": false,
+                "funding": false
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = site
+        .pages
+        .iter()
+        .find(|page| page.filename.ends_with("about.html"))
+        .unwrap();
+    assert_eq!(
+        selector_get_inner(&page.contents, "#features"),
+        "<li>Fast</li><li>Small</li>"
+    );
+    assert_eq!(
+        selector_get_inner(&page.contents, "#team"),
+        "Ferris, Mascot"
+    );
+    assert_eq!(
+        selector_get_inner(&page.contents, "#platforms"),
+        "true false"
+    );
+}
+
+#[test]
+fn it_points_at_the_bad_line_in_data_files() {
+    let data_dir = TempDir::new().unwrap();
+    let contents = "name = \"ok\"\nbroken = ]\nother = 1\n";
+    data_dir.child("broken.toml").write_str(contents).unwrap();
+    let err = data_files::load(Utf8Path::from_path(data_dir.path()).unwrap(), true).unwrap_err();
+    let OrandaError::DataFileParseFailed { path, span, .. } = err else {
+        panic!("expected a data file error, got {err:?}");
+    };
+    assert!(path.ends_with("broken.toml"));
+    // The error is on the second line
+    let offset = span.unwrap().offset();
+    assert!(offset >= contents.find("broken").unwrap());
+    assert!(offset < contents.find("other").unwrap());
+
+    // Missing things at the end of the file point at the end of the file
+    data_dir
+        .child("broken.toml")
+        .write_str("list = [\n")
+        .unwrap();
+    let err = data_files::load(Utf8Path::from_path(data_dir.path()).unwrap(), true).unwrap_err();
+    assert!(matches!(
+        err,
+        OrandaError::DataFileParseFailed { span: Some(_), .. }
+    ));
+}
+
+#[test]
+fn it_only_skips_bad_data_files_in_the_default_data_dir() {
+    let _guard = TEST_RUNTIME.enter();
+    let data_dir = TempDir::new().unwrap();
+    data_dir.child("broken.json").write_str("{").unwrap();
+    data_dir
+        .child("features.json")
+        .write_str(r#"["Fast"]"#)
+        .unwrap();
+    let data = data_files::load(Utf8Path::from_path(data_dir.path()).unwrap(), false).unwrap();
+    assert_eq!(data, json!({ "features": ["Fast"] }));
+
+    // Setting `build.data_dir` makes them errors again
+    let mut t = temp_build_dir();
+    let config = oranda_config::from_json(
+        json!({
+            "build": {
+                "data_dir": data_dir.path().display().to_string()
+            },
+            "components": {
+                "artifacts": false,
+                "changelog": false,
+                "funding": false
+            }
+        }),
+        &mut t,
+    );
+    assert!(config.build.data_dir.is_some());
+    assert!(matches!(
+        Site::build_single(&config, None),
+        Err(OrandaError::DataFileParseFailed { .. })
+    ));
+}
+
+#[test]
+fn it_orders_the_nav_from_config() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("CHANGELOG.md");
+    changelog
+        .write_str("# Changelog\n\n## 1.0.0\n\n- Hi\n")
+        .unwrap();
+    let page_path = project_dir.child("guide.md");
+    page_path.write_str("# Guide\n").unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "build": {
+                "additional_pages": {
+                    "Guide": page_path.path().display().to_string()
+                },
+                "nav": [
+                    { "builtin": "changelog", "label": "Releases" },
+                    { "url": "https://example.com/chat", "label": "Chat" },
+                    { "builtin": "install" }
+                ]
+            },
+            "components": {
+                "artifacts": false,
+                "mdbook": false,
+                "funding": false,
+                "changelog": {
+                    "mode": "file",
+                    "path": changelog.path().display().to_string()
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "changelog.html");
+    let doc = Html::parse_document(&page.contents);
+    let links = doc
+        .select(&Selector::parse("nav.nav a").unwrap())
+        .map(|a| a.inner_html())
+        .collect::<Vec<_>>();
+    // Home and the guide keep their usual places, and "install" isn't built
+    assert_eq!(links, ["Home", "Guide", "Releases", "Chat"]);
+    assert_selector_exists(
+        &page.contents,
+        "nav.nav a[href='https://example.com/chat'][rel='noopener noreferrer']",
+    );
+}
+
+#[test]
+fn it_rejects_invalid_nav_items() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let config = oranda_config::from_json(
+        json!({
+            "build": {
+                "nav": [
+                    { "url": "https://example.com/chat" }
+                ]
+            },
+            "components": {
+                "artifacts": false,
+                "changelog": false,
+                "funding": false
+            }
+        }),
+        &mut t,
+    );
+    let err = Site::build_single(&config, None).unwrap_err();
+    assert!(matches!(err, OrandaError::InvalidNavItem { index: 1, .. }));
+}
+
+#[test]
+fn it_renders_marketing_banners_and_footer() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let config = oranda_config::from_json(
+        json!({
+            "project": {
+                "repository": "https://codeberg.org/example/project"
+            },
+            "components": {
+                "artifacts": false,
+                "changelog": false,
+                "funding": false,
+                "mdbook": false
+            },
+            "marketing": {
+                "announcement": {
+                    "text": "We're [hiring](https://example.com/jobs)!",
+                    "expires": "2999-01-01"
+                },
+                "footer": {
+                    "text": "Made with care by [us](https://example.com)",
+                    "columns": [
+                        {
+                            "title": "Community",
+                            "links": [{ "label": "Chat", "url": "https://example.com/chat" }]
+                        }
+                    ]
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "index.html");
+    assert_selector_exists(
+        &page.contents,
+        ".announcement[data-expires='2999-01-01'] a[href='https://example.com/jobs']",
+    );
+    assert_selector_exists(&page.contents, ".announcement .announcement-dismiss");
+    let doc = Html::parse_document(&page.contents);
+    let banner = doc
+        .select(&Selector::parse(".repo_banner a").unwrap())
+        .next()
+        .unwrap();
+    // (The icon's title is in there too)
+    assert!(banner
+        .text()
+        .collect::<String>()
+        .trim_end()
+        .ends_with("Check out our Codeberg!"));
+    // Codeberg gets its own icon, and only GitHub gets the GitHub icon
+    assert_eq!(
+        selector_get_inner(&page.contents, ".repo_banner a .repo-icon svg title"),
+        "Codeberg"
+    );
+    assert!(doc
+        .select(&Selector::parse(".github-icon").unwrap())
+        .next()
+        .is_none());
+    assert_selector_exists(
+        &page.contents,
+        "footer .footer-column a[href='https://example.com/chat']",
+    );
+    assert_selector_exists(
+        &page.contents,
+        "footer .footer-text a[href='https://example.com']",
+    );
+}
+
+#[test]
+fn it_leaves_out_expired_announcements_and_disabled_repo_banners() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let marketing = |expires: &str| {
+        json!({
+            "project": {
+                "repository": "https://gitlab.com/example/project"
+            },
+            "components": {
+                "artifacts": false,
+                "changelog": false,
+                "funding": false,
+                "mdbook": false
+            },
+            "marketing": {
+                "announcement": { "text": "Old news", "expires": expires },
+                "repo_banner": false
+            }
+        })
+    };
+    let config = oranda_config::from_json(marketing("2000-01-01"), &mut t);
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "index.html");
+    let doc = Html::parse_document(&page.contents);
+    for selector in [".announcement", ".repo_banner"] {
+        assert!(doc
+            .select(&Selector::parse(selector).unwrap())
+            .next()
+            .is_none());
+    }
+    // The footer still links to the repository
+    assert_selector_exists(
+        &page.contents,
+        "footer a[href='https://gitlab.com/example/project'] .repo-icon",
+    );
+
+    let config = oranda_config::from_json(marketing("next tuesday"), &mut t);
+    let err = Site::build_single(&config, None).unwrap_err();
+    assert!(matches!(err, OrandaError::InvalidAnnouncementExpiry { .. }));
+}
+
+#[test]
+fn it_renders_changelog_entries_from_the_changelog_file() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    // Not in the build directory, since that's cleaned out first
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("HISTORY.md");
+    changelog
+        .write_str(
+            "# Changelog\n\n## [Unreleased]\n\n- Nothing yet\n\n\
+             ## [0.2.0] - 2023-04-05\n\n- **Fancy** thingie\n\n\
+             ## [0.1.0] - 2023-04-02\n\n- First release\n",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "components": {
+                "artifacts": false,
+                "funding": false,
+                "changelog": {
+                    "mode": "file",
+                    "path": changelog.path().display().to_string(),
+                    "rss_feed": false
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "changelog.html");
+    assert_selector_exists(&page.contents, "h2[id='tag-0.2.0']~.release-body li>strong");
+    assert_selector_exists(&page.contents, "h2[id='tag-0.1.0']");
+    assert!(selector_get_inner(&page.contents, ".release-info").contains("Apr  5 2023"));
+    assert!(!page.contents.contains("Nothing yet"));
+    find_page(&site.pages, "changelog/0.1.0.html");
+}
+
+#[test]
+fn it_falls_back_to_release_notes_the_changelog_file_lacks() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("CHANGELOG.md");
+    changelog
+        .write_str("# Changelog\n\n## 1.0.0\n\nFrom the changelog file\n")
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "components": {
+                "artifacts": {
+                    "tag_pattern": "axo-v{version}"
+                },
+                "changelog": {
+                    "path": changelog.path().display().to_string()
+                }
+            }
+        }),
+        &mut t,
+    );
+    let notes_for = |tag: &str| {
+        let source = ReleaseSource::Git(GitRelease {
+            tag_name: tag.to_owned(),
+            date: None,
+            body: Some("From the tag".to_owned()),
+            prerelease: false,
+        });
+        let release = TEST_RUNTIME.block_on(Release::new(source, None)).unwrap();
+        changelog::single_context(&release, None, &config, None).body
+    };
+
+    assert!(notes_for("axo-v1.0.0").contains("From the changelog file"));
+    assert!(notes_for("axo-v2.0.0").contains("From the tag"));
+}
+
+#[test]
+fn it_renders_changelog_categories() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("CHANGELOG.md");
+    changelog
+        .write_str(
+            "# Changelog\n\n## [1.0.1] - 2023-05-01\n\n### Fixed\n\n- A crash\n- A typo\n\n\
+             ### Security\n\n- A hole\n\n## [1.0.0] - 2023-04-02\n\n- First release\n",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "components": {
+                "artifacts": false,
+                "funding": false,
+                "changelog": {
+                    "mode": "file",
+                    "path": changelog.path().display().to_string(),
+                    "json_feed": true
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+    let page = find_page(&site.pages, "changelog.html");
+    assert_selector_exists(
+        &page.contents,
+        ".release.security-release h2[id='tag-1.0.1']",
+    );
+    assert_selector_exists(
+        &page.contents,
+        "section[id='tag-1.0.1-fixed'] a.release-badge-fixed[href='#tag-1.0.1-fixed']",
+    );
+    assert_eq!(
+        selector_get_inner(&page.contents, ".release-info .release-badge-fixed"),
+        "2 fixed"
+    );
+    // Releases without categories are rendered like before
+    assert_selector_exists(&page.contents, "h2[id='tag-1.0.0']~.release-body>ul");
+
+    let rss = find_page(&site.pages, "changelog.rss");
+    assert!(rss
+        .contents
+        .contains("<description><![CDATA[2 fixed, 1 security]]></description>"));
+    let json_feed: serde_json::Value =
+        serde_json::from_str(&find_page(&site.pages, "changelog.json").contents).unwrap();
+    assert_eq!(json_feed["items"][0]["summary"], "2 fixed, 1 security");
+    assert_eq!(json_feed["items"][0]["tags"], json!(["fixed", "security"]));
+}
+
+#[test]
+fn it_paginates_the_changelog() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("CHANGELOG.md");
+    changelog
+        .write_str(
+            "# Changelog\n\n## 1.1.0\n\n- Newest\n\n## 1.0.1\n\n- Newer\n\n## 1.0.0\n\n- Oldest\n",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "components": {
+                "artifacts": false,
+                "funding": false,
+                "changelog": {
+                    "mode": "file",
+                    "path": changelog.path().display().to_string(),
+                    "per_page": 2,
+                    "group_nav": true
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+
+    let first = find_page(&site.pages, "changelog.html");
+    assert_selector_exists(&first.contents, "h2[id='tag-1.1.0']");
+    assert_selector_exists(&first.contents, "h2[id='tag-1.0.1']");
+    assert_selector_exists(
+        &first.contents,
+        ".releases-pagination a[href='/changelog/page/2/']",
+    );
+    assert_eq!(
+        selector_get_inner(&first.contents, ".releases-nav-group[open] summary"),
+        "1.1"
+    );
+    assert_selector_exists(
+        &first.contents,
+        ".releases-nav-group a[href='/changelog/1.0.1/']",
+    );
+
+    let second = find_page(&site.pages, "changelog/page/2.html");
+    assert_selector_exists(&second.contents, "h2[id='tag-1.0.0']");
+    assert_selector_exists(
+        &second.contents,
+        ".releases-pagination a[href='/changelog/']",
+    );
+
+    // The feed and the pages for each release aren't paginated, but the feed
+    // says how many pages there are
+    let feed = find_page(&site.pages, "changelog.rss");
+    assert_eq!(feed.contents.matches("<item>").count(), 3);
+    assert!(feed
+        .contents
+        .contains("<oranda:pageCount>2</oranda:pageCount>"));
+    assert!(feed
+        .contents
+        .contains("xmlns:oranda=\"https://opensource.axo.dev/oranda/\""));
+    find_page(&site.pages, "changelog/1.0.0.html");
+}
+
+#[test]
+fn it_generates_changelog_feeds() {
+    let _guard = TEST_RUNTIME.enter();
+    let mut t = temp_build_dir();
+    let project_dir = TempDir::new().unwrap();
+    let changelog = project_dir.child("CHANGELOG.md");
+    changelog
+        .write_str(
+            "# Changelog\n\n## 0.3.0 - 2023-05-01\n\n- Newest\n\n\
+             ## 0.2.0 - 2023-04-05\n\n- Newer\n\n## 0.1.0 - 2023-04-02\n\n- Oldest\n",
+        )
+        .unwrap();
+    let config = oranda_config::from_json(
+        json!({
+            "components": {
+                "artifacts": false,
+                "funding": false,
+                "changelog": {
+                    "mode": "file",
+                    "path": changelog.path().display().to_string(),
+                    "atom_feed": true,
+                    "json_feed": true,
+                    "feed_limit": 2
+                }
+            }
+        }),
+        &mut t,
+    );
+    let site = Site::build_single(&config, None).unwrap();
+
+    let rss = find_page(&site.pages, "changelog.rss");
+    assert_eq!(rss.contents.matches("<item>").count(), 2);
+    // The newest release, and the channel (which is as new as its newest release)
+    assert_eq!(
+        rss.contents
+            .matches("<pubDate>Mon, 1 May 2023 00:00:00 +0000</pubDate>")
+            .count(),
+        2
+    );
+    // The channel was built just now, not when the newest release came out
+    assert!(rss.contents.contains("<lastBuildDate>"));
+    assert!(!rss.contents.contains("<lastBuildDate>Mon, 1 May 2023"));
+    assert!(rss.contents.contains("<dc:creator>oranda</dc:creator>"));
+    assert!(!rss.contents.contains("Oldest"));
+
+    let atom = find_page(&site.pages, "changelog.atom");
+    assert_eq!(atom.contents.matches("<entry>").count(), 2);
+    assert!(atom
+        .contents
+        .contains("<updated>2023-05-01T00:00:00+00:00</updated>"));
+    assert!(atom
+        .contents
+        .contains("<published>2023-04-05T00:00:00+00:00</published>"));
+    assert!(atom
+        .contents
+        .contains("<oranda:pageCount>1</oranda:pageCount>"));
+
+    let json_feed: serde_json::Value =
+        serde_json::from_str(&find_page(&site.pages, "changelog.json").contents).unwrap();
+    assert_eq!(json_feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(json_feed["items"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        json_feed["items"][0]["date_published"],
+        "2023-05-01T00:00:00Z"
+    );
+    assert_eq!(json_feed["_oranda"]["page_count"], 1);
+
+    let index = find_page(&site.pages, "index.html");
+    assert_selector_exists(
+        &index.contents,
+        "link[rel='alternate'][type='application/rss+xml'][href='/changelog.rss']",
+    );
+    assert_selector_exists(
+        &index.contents,
+        "link[rel='alternate'][type='application/atom+xml'][href='/changelog.atom']",
+    );
+    assert_selector_exists(
+        &index.contents,
+        "link[rel='alternate'][type='application/feed+json'][href='/changelog.json']",
+    );
+}
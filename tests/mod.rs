@@ -1,5 +1,8 @@
 pub mod utils;
 
+mod artifacts;
 mod autodetect;
+mod http_cache;
 mod integration;
 mod integration_gallery;
+mod releases;
@@ -0,0 +1,927 @@
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use oranda::config::{
+    ArtifactsConfig, ComponentConfig, ForgeConfig, LocalReleasesConfig, ProjectConfig,
+    ReleasesSource,
+};
+use oranda::data::changelog::{
+    date_from_heading, ChangelogCategory, ChangelogEntry, ChangelogSections,
+};
+use oranda::data::forgejo::{ForgejoRelease, ForgejoRepo};
+use oranda::data::git::{self, GitRelease};
+use oranda::data::github::{GithubApi, GithubRelease};
+use oranda::data::gitlab::{GitlabRelease, GitlabRepo};
+use oranda::data::repo_links::RepoLinks;
+use oranda::data::{Context, CurrentStateRelease, Release, ReleaseSource};
+use oranda::site::markdown::{self, SyntaxTheme, TextSpan};
+
+use crate::utils::stand_in::{response, StandIn};
+use crate::utils::tokio_utils::TEST_RUNTIME;
+
+/// Make a Context out of some tags (from newest to oldest), with "-pre" ones being prereleases
+fn context_for_tags(tags: &[&str], artifacts_config: &ArtifactsConfig) -> Context {
+    let _guard = TEST_RUNTIME.enter();
+    let releases = tags
+        .iter()
+        .map(|tag| {
+            let source = ReleaseSource::CurrentState(CurrentStateRelease {
+                version: Some(tag.to_string()),
+                date: None,
+                prerelease: tag.ends_with("-pre"),
+            });
+            TEST_RUNTIME.block_on(Release::new(source, None)).unwrap()
+        })
+        .collect();
+    Context::with_releases(
+        None,
+        releases,
+        Some(artifacts_config),
+        &ProjectConfig::default(),
+    )
+}
+
+fn latest_tag(context: &Context) -> Option<&str> {
+    context.latest().map(|r| r.source.version_tag())
+}
+
+#[test]
+fn it_picks_the_highest_version_as_latest() {
+    let context = context_for_tags(
+        &["v1.4.9", "v2.0.0", "v1.4.8", "v3.0.0-beta.1"],
+        &ArtifactsConfig::default(),
+    );
+    assert_eq!(latest_tag(&context), Some("v2.0.0"));
+    assert!(context.has_prereleases);
+}
+
+#[test]
+fn it_falls_back_to_the_newest_unversioned_release() {
+    let context = context_for_tags(&["nightly", "latest"], &ArtifactsConfig::default());
+    assert_eq!(latest_tag(&context), Some("nightly"));
+}
+
+#[test]
+fn it_filters_releases_by_tag_pattern() {
+    let config = ArtifactsConfig {
+        tag_pattern: Some("axo-v{version}".to_owned()),
+        ..ArtifactsConfig::default()
+    };
+    let context = context_for_tags(&["other-v9.0.0", "axo-v1.0.0", "axo-v1.1.0"], &config);
+    assert_eq!(latest_tag(&context), Some("axo-v1.1.0"));
+    // The other releases don't show up anywhere else either, like the changelog
+    let tags: Vec<_> = context
+        .releases
+        .iter()
+        .map(|r| r.source.version_tag())
+        .collect();
+    assert_eq!(tags, vec!["axo-v1.0.0", "axo-v1.1.0"]);
+
+    let context = context_for_tags(&["other-v9.0.0", "axo-v2.0.0-pre"], &config);
+    assert_eq!(latest_tag(&context), Some("axo-v2.0.0-pre"));
+}
+
+#[test]
+fn it_reads_local_releases_from_tag_directories() {
+    let _guard = TEST_RUNTIME.enter();
+    let dir = TempDir::new().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        dir.child(format!("{tag}/axo-x86_64-unknown-linux-gnu.tar.gz"))
+            .write_str("")
+            .unwrap();
+        dir.child(format!("{tag}/axo-installer.sh"))
+            .write_str("")
+            .unwrap();
+    }
+    let local_config = LocalReleasesConfig {
+        paths: vec![dir.path().to_str().unwrap().to_owned()],
+        download_url: Some("https://example.com/{version}/{file}".to_owned()),
+    };
+    let artifacts_config = ArtifactsConfig {
+        auto: true,
+        ..ArtifactsConfig::default()
+    };
+
+    let context = Context::new_local(
+        &local_config,
+        None,
+        &ProjectConfig::default(),
+        Some(&artifacts_config),
+    )
+    .unwrap();
+    assert_eq!(context.releases.len(), 2);
+    let latest = context.latest().unwrap();
+    assert_eq!(latest.source.version_tag(), "v1.1.0");
+    let urls = latest
+        .artifacts
+        .files()
+        .map(|f| f.download_url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/1.1.0/axo-installer.sh",
+            "https://example.com/1.1.0/axo-x86_64-unknown-linux-gnu.tar.gz",
+        ]
+    );
+    assert!(latest.has_installers());
+}
+
+#[test]
+fn it_only_shows_the_packages_own_artifacts_by_default() {
+    let _guard = TEST_RUNTIME.enter();
+    let dir = TempDir::new().unwrap();
+    for name in [
+        "axo-x86_64-unknown-linux-gnu.tar.gz",
+        "lotl-x86_64-unknown-linux-gnu.tar.gz",
+        "install.sh",
+    ] {
+        dir.child(format!("v1.0.0/{name}")).write_str("").unwrap();
+    }
+    let local_config = LocalReleasesConfig {
+        paths: vec![dir.path().to_str().unwrap().to_owned()],
+        download_url: Some("https://example.com/{version}/{file}".to_owned()),
+    };
+    // The package name, which `apps` defaults to
+    let artifacts_config = ArtifactsConfig {
+        auto: true,
+        default_app: Some("axo".to_owned()),
+        ..ArtifactsConfig::default()
+    };
+    let context = Context::new_local(
+        &local_config,
+        None,
+        &ProjectConfig::default(),
+        Some(&artifacts_config),
+    )
+    .unwrap();
+    let latest = context.latest().unwrap();
+    let labels = |target: &str| {
+        latest.artifacts.installers_by_target()[target]
+            .iter()
+            .map(|idx| latest.artifacts.installer(*idx).label.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(labels("x86_64-unknown-linux-gnu"), vec!["shell", "tarball"]);
+
+    // Setting `apps` explicitly wins
+    let artifacts_config = ArtifactsConfig {
+        apps: vec!["lotl".to_owned()],
+        ..artifacts_config
+    };
+    assert_eq!(artifacts_config.shown_apps(), vec!["lotl"]);
+}
+
+#[test]
+fn it_errors_without_local_releases() {
+    let local_config = LocalReleasesConfig {
+        paths: vec!["this/does/not/exist".to_owned()],
+        download_url: None,
+    };
+    assert!(Context::new_local(&local_config, None, &ProjectConfig::default(), None).is_err());
+}
+
+#[test]
+fn it_detects_gitlab_repositories() {
+    let components = ComponentConfig::default();
+    for url in [
+        "https://gitlab.com/axo/oranda",
+        "git@gitlab.example.com:axo/oranda.git",
+    ] {
+        assert!(matches!(
+            components.releases_source(Some(url)),
+            ReleasesSource::GitLab
+        ));
+    }
+    assert!(matches!(
+        components.releases_source(Some("https://github.com/axodotdev/oranda")),
+        ReleasesSource::GitHub
+    ));
+}
+
+#[test]
+fn it_parses_gitlab_project_urls() {
+    let repo = GitlabRepo::from_url(
+        "https://gitlab.example.com/group/subgroup/project/-/releases",
+        &ForgeConfig::default(),
+    )
+    .unwrap();
+    assert_eq!(repo.base_url, "https://gitlab.example.com");
+    assert_eq!(repo.path, "group/subgroup/project");
+
+    let config = ForgeConfig {
+        base_url: Some("https://api.example.com/".to_owned()),
+        token_env: None,
+    };
+    let repo = GitlabRepo::from_url("git@gitlab.example.com:group/project.git", &config).unwrap();
+    assert_eq!(repo.base_url, "https://api.example.com");
+    assert_eq!(repo.path, "group/project");
+}
+
+#[test]
+fn it_reads_gitlab_release_links() {
+    let _guard = TEST_RUNTIME.enter();
+    let gitlab_release: GitlabRelease = serde_json::from_value(serde_json::json!({
+        "tag_name": "v2.0.0-rc.1",
+        "name": "Release candidate",
+        "description": "Some notes",
+        "created_at": "2023-01-01T00:00:00Z",
+        "released_at": "2023-01-02T00:00:00Z",
+        "upcoming_release": false,
+        "assets": {
+            "links": [{
+                "name": "axo-x86_64-unknown-linux-gnu.tar.gz",
+                "url": "https://gitlab.com/axo/axo/-/jobs/1/artifacts/file.tar.gz",
+                "direct_asset_url": "https://gitlab.com/axo/axo/-/releases/v2.0.0-rc.1/downloads/axo.tar.gz",
+                "link_type": "package"
+            }],
+            "sources": []
+        }
+    }))
+    .unwrap();
+    let artifacts_config = ArtifactsConfig {
+        auto: true,
+        ..ArtifactsConfig::default()
+    };
+    let release = TEST_RUNTIME
+        .block_on(Release::new(
+            ReleaseSource::Gitlab(gitlab_release),
+            Some(&artifacts_config),
+        ))
+        .unwrap();
+
+    assert!(release.source.is_prerelease());
+    assert_eq!(release.source.date(), Some("2023-01-02T00:00:00Z"));
+    let file = release.artifacts.files().next().unwrap();
+    assert_eq!(
+        file.download_url,
+        "https://gitlab.com/axo/axo/-/releases/v2.0.0-rc.1/downloads/axo.tar.gz"
+    );
+    assert!(release.has_installers());
+}
+
+#[test]
+fn it_sends_the_gitlab_token_for_the_dist_manifest() {
+    let _guard = TEST_RUNTIME.enter();
+    let stand_in = StandIn::serve(vec![response(
+        "200 OK",
+        &[],
+        r#"{"releases": [], "artifacts": {}}"#,
+    )]);
+    let gitlab_release: GitlabRelease = serde_json::from_value(serde_json::json!({
+        "tag_name": "v1.0.0",
+        "name": null,
+        "description": null,
+        "created_at": "2023-01-01T00:00:00Z",
+        "released_at": null,
+        "assets": {
+            "links": [{
+                "name": "dist-manifest.json",
+                "url": format!("{}/dist-manifest.json", stand_in.url),
+                "direct_asset_url": null,
+                "link_type": "other"
+            }]
+        }
+    }))
+    .unwrap();
+    std::env::set_var("ORANDA_TEST_GITLAB_TOKEN", "hunter2");
+    let forge_config = ForgeConfig {
+        base_url: None,
+        token_env: Some("ORANDA_TEST_GITLAB_TOKEN".to_owned()),
+    };
+    let artifacts_config = ArtifactsConfig {
+        cargo_dist: true,
+        ..ArtifactsConfig::default()
+    };
+    let release = TEST_RUNTIME
+        .block_on(Release::with_forge(
+            ReleaseSource::Gitlab(gitlab_release),
+            Some(&artifacts_config),
+            &forge_config,
+        ))
+        .unwrap();
+
+    assert!(release.manifest.is_some());
+    let requests = stand_in.requests();
+    assert!(requests[0].starts_with("GET /dist-manifest.json "));
+    assert!(requests[0]
+        .to_lowercase()
+        .contains("private-token: hunter2"));
+}
+
+#[test]
+fn it_detects_forgejo_repositories() {
+    let components = ComponentConfig::default();
+    for url in [
+        "https://codeberg.org/axo/oranda",
+        "https://gitea.example.com/axo/oranda.git",
+    ] {
+        assert!(matches!(
+            components.releases_source(Some(url)),
+            ReleasesSource::Forgejo
+        ));
+    }
+    let repo =
+        ForgejoRepo::from_url("https://codeberg.org/axo/oranda", &ForgeConfig::default()).unwrap();
+    assert_eq!(repo.base_url, "https://codeberg.org");
+    assert_eq!((repo.owner.as_str(), repo.name.as_str()), ("axo", "oranda"));
+}
+
+#[test]
+fn it_reads_forgejo_release_attachments() {
+    let _guard = TEST_RUNTIME.enter();
+    let forgejo_release: ForgejoRelease = serde_json::from_value(serde_json::json!({
+        "tag_name": "v1.0.0",
+        "name": "v1.0.0",
+        "body": "## Fixes\n\n- Fixed it",
+        "draft": false,
+        "prerelease": false,
+        "created_at": "2023-01-01T00:00:00Z",
+        "published_at": "2023-01-02T00:00:00Z",
+        "assets": [{
+            "name": "axo-x86_64-pc-windows-msvc.zip",
+            "browser_download_url": "https://codeberg.org/axo/axo/releases/download/v1.0.0/axo-x86_64-pc-windows-msvc.zip"
+        }]
+    }))
+    .unwrap();
+    let artifacts_config = ArtifactsConfig {
+        auto: true,
+        ..ArtifactsConfig::default()
+    };
+    let release = TEST_RUNTIME
+        .block_on(Release::new(
+            ReleaseSource::Forgejo(forgejo_release),
+            Some(&artifacts_config),
+        ))
+        .unwrap();
+
+    assert!(!release.source.is_prerelease());
+    assert_eq!(release.source.date(), Some("2023-01-02T00:00:00Z"));
+    assert!(release.has_installers());
+    assert_eq!(
+        release.artifacts.files().next().unwrap().download_url,
+        "https://codeberg.org/axo/axo/releases/download/v1.0.0/axo-x86_64-pc-windows-msvc.zip"
+    );
+}
+
+/// Make a page of GitHub releases tagged with these versions
+fn github_releases_page(versions: impl Iterator<Item = usize>) -> String {
+    let releases = versions
+        .map(|version| {
+            let tag = format!("v{version}.0.0");
+            serde_json::json!({
+                "url": "",
+                "assets_url": "",
+                "html_url": format!("https://github.com/axo/axo/releases/tag/{tag}"),
+                "id": version,
+                "tag_name": tag,
+                "target_commitish": "main",
+                "name": tag,
+                "draft": false,
+                "prerelease": false,
+                "created_at": "2023-01-01T00:00:00Z",
+                "published_at": "2023-01-01T00:00:00Z",
+                "assets": [],
+                "tarball_url": "",
+                "zipball_url": "",
+                "body": null
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&releases).unwrap()
+}
+
+fn forgejo_releases_page(versions: impl Iterator<Item = usize>) -> String {
+    let releases = versions
+        .map(|version| {
+            serde_json::json!({
+                "tag_name": format!("v{version}.0.0"),
+                "name": null,
+                "body": null,
+                "created_at": "2023-01-01T00:00:00Z",
+                "published_at": null
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&releases).unwrap()
+}
+
+#[test]
+fn it_keeps_paging_when_forgejo_sends_short_pages() {
+    let _guard = TEST_RUNTIME.enter();
+    // Instances can cap pages below the size we ask for, so a short page isn't the last one
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], &forgejo_releases_page(1..=30)),
+        response("200 OK", &[], &forgejo_releases_page(31..=35)),
+        response("200 OK", &[], "[]"),
+    ]);
+    let config = ForgeConfig {
+        base_url: Some(stand_in.url.clone()),
+        token_env: Some("ORANDA_TEST_UNSET_TOKEN".to_owned()),
+    };
+
+    let context = Context::new_forgejo(
+        "https://codeberg.org/axo/axo",
+        &config,
+        &ProjectConfig::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(context.releases.len(), 35);
+    let requests = stand_in.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[2].starts_with("GET /api/v1/repos/axo/axo/releases?limit=50&page=3 "));
+}
+
+#[test]
+fn it_stops_paging_when_forgejo_ignores_the_page() {
+    let _guard = TEST_RUNTIME.enter();
+    // A server (or proxy) that ignores `page` would otherwise be asked forever
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], &forgejo_releases_page(1..=30)),
+        response("200 OK", &[], &forgejo_releases_page(1..=30)),
+    ]);
+    let config = ForgeConfig {
+        base_url: Some(stand_in.url.clone()),
+        token_env: Some("ORANDA_TEST_UNSET_TOKEN".to_owned()),
+    };
+
+    let context = Context::new_forgejo(
+        "https://codeberg.org/axo/axo",
+        &config,
+        &ProjectConfig::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(context.releases.len(), 30);
+    assert_eq!(stand_in.requests().len(), 2);
+}
+
+#[test]
+fn it_sends_the_forgejo_token_for_the_dist_manifest() {
+    let _guard = TEST_RUNTIME.enter();
+    let files = StandIn::serve(vec![response(
+        "200 OK",
+        &[],
+        r#"{"releases": [], "artifacts": {}}"#,
+    )]);
+    let releases = serde_json::json!([{
+        "tag_name": "v1.0.0",
+        "name": null,
+        "body": null,
+        "created_at": "2023-01-01T00:00:00Z",
+        "published_at": null,
+        "assets": [{
+            "name": "dist-manifest.json",
+            "browser_download_url": format!("{}/dist-manifest.json", files.url)
+        }]
+    }]);
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], &releases.to_string()),
+        response("200 OK", &[], "[]"),
+    ]);
+    std::env::set_var("ORANDA_TEST_FORGEJO_TOKEN", "hunter2");
+    let config = ForgeConfig {
+        base_url: Some(stand_in.url.clone()),
+        token_env: Some("ORANDA_TEST_FORGEJO_TOKEN".to_owned()),
+    };
+    let artifacts_config = ArtifactsConfig {
+        cargo_dist: true,
+        ..ArtifactsConfig::default()
+    };
+
+    let context = Context::new_forgejo(
+        "https://codeberg.org/axo/axo",
+        &config,
+        &ProjectConfig::default(),
+        Some(&artifacts_config),
+    )
+    .unwrap();
+
+    assert!(context.releases[0].manifest.is_some());
+    let requests = files.requests();
+    assert!(requests[0].starts_with("GET /dist-manifest.json "));
+    assert!(requests[0]
+        .to_lowercase()
+        .contains("authorization: token hunter2"));
+}
+
+#[test]
+fn it_finds_the_github_api_for_a_repo() {
+    let config = ForgeConfig::default();
+    let api = GithubApi::from_url("https://github.com/axodotdev/oranda", &config).unwrap();
+    assert_eq!(api.api_url, "https://api.github.com");
+    assert_eq!(
+        (api.repo.owner.as_str(), api.repo.name.as_str()),
+        ("axodotdev", "oranda")
+    );
+
+    let api = GithubApi::from_url("git@github.example.com:axo/oranda.git", &config).unwrap();
+    assert_eq!(api.api_url, "https://github.example.com/api/v3");
+
+    let config = ForgeConfig {
+        base_url: Some("https://ghe.example.com/".to_owned()),
+        token_env: None,
+    };
+    let api = GithubApi::from_url("https://github.com/axodotdev/oranda", &config).unwrap();
+    assert_eq!(api.api_url, "https://ghe.example.com/api/v3");
+}
+
+#[test]
+fn it_follows_github_pagination_with_a_token() {
+    let _guard = TEST_RUNTIME.enter();
+    let stand_in = StandIn::serve(vec![
+        response("200 OK", &[], &github_releases_page(1..=100)),
+        response("200 OK", &[], &github_releases_page(101..=102)),
+    ]);
+    std::env::set_var("ORANDA_TEST_PAGINATION_TOKEN", "hunter2");
+    let config = ForgeConfig {
+        base_url: Some(stand_in.url.clone()),
+        token_env: Some("ORANDA_TEST_PAGINATION_TOKEN".to_owned()),
+    };
+
+    let context = Context::new_github(
+        "https://github.com/axo/axo",
+        &config,
+        &ProjectConfig::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(context.releases.len(), 102);
+    assert_eq!(
+        context.latest().map(|r| r.source.version_tag()),
+        Some("v102.0.0")
+    );
+    let requests = stand_in.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].starts_with("GET /api/v3/repos/axo/axo/releases?per_page=100&page=1 "));
+    assert!(requests[1].starts_with("GET /api/v3/repos/axo/axo/releases?per_page=100&page=2 "));
+    assert!(requests
+        .iter()
+        .all(|r| r.to_lowercase().contains("authorization: bearer hunter2")));
+}
+
+#[test]
+fn it_fetches_the_github_dist_manifest_through_the_api() {
+    let _guard = TEST_RUNTIME.enter();
+    let stand_in = StandIn::serve(vec![response(
+        "200 OK",
+        &[],
+        r#"{"releases": [], "artifacts": {}}"#,
+    )]);
+    let gh_release: GithubRelease = serde_json::from_value(serde_json::json!({
+        "url": "",
+        "assets_url": "",
+        "html_url": "https://github.com/axo/axo/releases/tag/v1.0.0",
+        "id": 1,
+        "tag_name": "v1.0.0",
+        "target_commitish": "main",
+        "name": null,
+        "draft": false,
+        "prerelease": false,
+        "created_at": "2023-01-01T00:00:00Z",
+        "published_at": "2023-01-01T00:00:00Z",
+        "assets": [{
+            "url": format!("{}/repos/axo/axo/releases/assets/7", stand_in.url),
+            "id": 7,
+            "node_id": "",
+            "name": "dist-manifest.json",
+            "label": null,
+            "content_type": "application/json",
+            "state": "uploaded",
+            "size": 35,
+            "download_count": 0,
+            "created_at": "2023-01-01T00:00:00Z",
+            "updated_at": "2023-01-01T00:00:00Z",
+            "browser_download_url": "https://github.com/axo/axo/releases/download/v1.0.0/dist-manifest.json"
+        }],
+        "tarball_url": "",
+        "zipball_url": "",
+        "body": null
+    }))
+    .unwrap();
+    std::env::set_var("ORANDA_TEST_MANIFEST_TOKEN", "hunter2");
+    let forge_config = ForgeConfig {
+        base_url: None,
+        token_env: Some("ORANDA_TEST_MANIFEST_TOKEN".to_owned()),
+    };
+    let artifacts_config = ArtifactsConfig {
+        cargo_dist: true,
+        ..ArtifactsConfig::default()
+    };
+    let release = TEST_RUNTIME
+        .block_on(Release::with_forge(
+            ReleaseSource::Github(gh_release),
+            Some(&artifacts_config),
+            &forge_config,
+        ))
+        .unwrap();
+
+    assert!(release.manifest.is_some());
+    let request = stand_in.requests()[0].to_lowercase();
+    assert!(request.starts_with("get /repos/axo/axo/releases/assets/7 "));
+    assert!(request.contains("accept: application/octet-stream"));
+    assert!(request.contains("authorization: bearer hunter2"));
+}
+
+#[test]
+fn it_retries_when_github_rate_limits() {
+    let _guard = TEST_RUNTIME.enter();
+    let stand_in = StandIn::serve(vec![
+        response("429 Too Many Requests", &[("retry-after", "0")], ""),
+        response(
+            "403 Forbidden",
+            &[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "0")],
+            "",
+        ),
+        response("200 OK", &[], &github_releases_page(1..=2)),
+    ]);
+    let config = ForgeConfig {
+        base_url: Some(stand_in.url.clone()),
+        token_env: Some("ORANDA_TEST_UNSET_TOKEN".to_owned()),
+    };
+
+    let context = Context::new_github(
+        "https://github.com/axo/axo",
+        &config,
+        &ProjectConfig::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(context.releases.len(), 2);
+    assert_eq!(stand_in.requests().len(), 3);
+    assert!(!stand_in.requests()[0]
+        .to_lowercase()
+        .contains("authorization"));
+}
+
+#[test]
+fn it_reads_version_tags_from_git() {
+    let dir = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=axo", "-c", "user.email=axo@example.com"])
+            .args(args)
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "--quiet"]);
+    git(&["commit", "--quiet", "--allow-empty", "-m", "Initial commit"]);
+    git(&["tag", "-a", "v1.0.0", "-m", "The first release"]);
+    git(&["tag", "v1.1.0-beta.1"]);
+    git(&["tag", "docs-refresh"]);
+    // A signed tag, which is easier to make by hand than to set up signing for
+    let head = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let signed_tag = format!(
+        "object {}\ntype commit\ntag v0.9.0\ntagger axo <axo@example.com> 1700000000 +0000\n\n\
+         The beta release\n\nWith more notes\n\
+         -----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----\n",
+        String::from_utf8_lossy(&head.stdout).trim()
+    );
+    dir.child("signed-tag").write_str(&signed_tag).unwrap();
+    let tag_object = std::process::Command::new("git")
+        .args(["hash-object", "-t", "tag", "-w", "signed-tag"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    git(&[
+        "update-ref",
+        "refs/tags/v0.9.0",
+        String::from_utf8_lossy(&tag_object.stdout).trim(),
+    ]);
+
+    let path = camino::Utf8Path::from_path(dir.path()).unwrap();
+    let releases = GitRelease::load_all(path, None);
+
+    let tags = releases
+        .iter()
+        .map(|r| r.tag_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(tags, ["v1.1.0-beta.1", "v1.0.0", "v0.9.0"]);
+    assert!(releases[0].prerelease);
+    assert_eq!(releases[0].body, None);
+    assert!(!releases[1].prerelease);
+    assert_eq!(releases[1].body.as_deref(), Some("The first release"));
+    assert!(releases[1].date.is_some());
+    // The signature isn't part of the release notes
+    assert_eq!(
+        releases[2].body.as_deref(),
+        Some("The beta release\n\nWith more notes")
+    );
+
+    let releases = GitRelease::load_all(path, Some("docs-{version}"));
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].tag_name, "docs-refresh");
+}
+
+#[test]
+fn it_has_no_git_tags_outside_a_repository() {
+    let dir = TempDir::new().unwrap();
+    let path = camino::Utf8Path::from_path(dir.path()).unwrap();
+    assert!(GitRelease::load_all(path, None).is_empty());
+}
+
+#[test]
+fn it_uses_git_tags_without_a_repository() {
+    let components = ComponentConfig::default();
+    assert!(matches!(
+        components.releases_source(None),
+        ReleasesSource::Git
+    ));
+}
+
+#[test]
+fn it_reads_dates_from_changelog_headings() {
+    assert_eq!(
+        date_from_heading("[1.0.0] - 2017-06-20").as_deref(),
+        Some("2017-06-20")
+    );
+    assert_eq!(
+        date_from_heading("v1.0.0 (2017-06-20)").as_deref(),
+        Some("2017-06-20")
+    );
+    assert_eq!(
+        date_from_heading("1.0.0 – 2017-06-20").as_deref(),
+        Some("2017-06-20")
+    );
+    assert_eq!(date_from_heading("1.0.0"), None);
+    assert_eq!(date_from_heading("1.0.0 - 2017-13-40"), None);
+}
+
+#[test]
+fn it_reads_entries_from_changelog_files() {
+    let dir = TempDir::new().unwrap();
+    let root = camino::Utf8Path::from_path(dir.path()).unwrap();
+    assert!(ChangelogEntry::find_file(root, None).is_none());
+    dir.child("CHANGES.md")
+        .write_str(
+            "# Changes\n\n## Unreleased\n\n- Soon\n\n\
+             ## v1.1.0-beta.1 - 2023-05-01\n\n- Trying things\n\n\
+             ## 1.0.0\n\n- Done\n",
+        )
+        .unwrap();
+    let path = ChangelogEntry::find_file(root, None).unwrap();
+    assert_eq!(path, root.join("CHANGES.md"));
+
+    let entries = ChangelogEntry::load_all(&path).unwrap();
+    let versions = entries
+        .iter()
+        .map(|entry| entry.version.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(versions, ["1.1.0-beta.1", "1.0.0"]);
+    assert!(entries[0].prerelease);
+    assert_eq!(entries[0].date.as_deref(), Some("2023-05-01"));
+    assert_eq!(entries[0].notes, "- Trying things");
+    assert!(!entries[1].prerelease);
+    assert_eq!(entries[1].date, None);
+
+    // An explicit path is used even if a usual one exists
+    assert_eq!(
+        ChangelogEntry::find_file(root, Some("docs/RELEASES.md")),
+        Some(root.join("docs/RELEASES.md"))
+    );
+}
+
+#[test]
+fn it_splits_release_notes_into_categories() {
+    let sections = ChangelogSections::parse(
+        "A big one!\n\n### Added\n\n- New thing\n  - with details\n- Other thing\n\n\
+         ### Changed\n\n### fixed\n\n1. A bug\n\n```md\n### Not a heading\n- Not an entry\n```\n\n\
+         ### Security\n\n- A hole\n\n#### Details\n\n- More\n\n### Thanks\n\nEveryone\n",
+    )
+    .unwrap();
+    assert_eq!(sections.intro.trim(), "A big one!");
+    let categories = sections
+        .sections
+        .iter()
+        .map(|section| (section.category, section.title.as_str(), section.entries))
+        .collect::<Vec<_>>();
+    // The empty "Changed" section is left out
+    assert_eq!(
+        categories,
+        [
+            (ChangelogCategory::Added, "Added", 2),
+            (ChangelogCategory::Fixed, "fixed", 1),
+            (ChangelogCategory::Security, "Security", 2),
+            (ChangelogCategory::Other, "Thanks", 0),
+        ]
+    );
+    assert!(sections.sections[1].notes.contains("### Not a heading"));
+    assert!(sections.sections[2].notes.contains("#### Details"));
+    assert!(sections.has_security());
+
+    assert!(ChangelogSections::parse("## Highlights\n\n- Stuff\n").is_none());
+}
+
+#[test]
+fn it_links_references_to_the_repository() {
+    let github = RepoLinks::new(
+        "https://github.com/axodotdev/oranda.git",
+        &ReleasesSource::GitHub,
+    )
+    .unwrap();
+    assert_eq!(
+        github.compare("v0.1.0", "v0.2.0"),
+        "https://github.com/axodotdev/oranda/compare/v0.1.0...v0.2.0"
+    );
+    let spans =
+        github.linkify("Fixed #12 and GH-34 (thanks @ashley-h!) in 1a2b3c4d, see me@example.com");
+    let links = spans
+        .iter()
+        .filter_map(|span| match span {
+            TextSpan::Link { text, url } => Some((text.as_str(), url.as_str())),
+            TextSpan::Text(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        links,
+        [
+            ("#12", "https://github.com/axodotdev/oranda/issues/12"),
+            ("GH-34", "https://github.com/axodotdev/oranda/issues/34"),
+            ("@ashley-h", "https://github.com/ashley-h"),
+            (
+                "1a2b3c4d",
+                "https://github.com/axodotdev/oranda/commit/1a2b3c4d"
+            ),
+        ]
+    );
+    // Plain words and numbers aren't commits
+    assert_eq!(
+        github.linkify("added 1234567 deadbeef"),
+        [TextSpan::Text("added 1234567 deadbeef".to_owned())]
+    );
+
+    let gitlab = RepoLinks::new("git@gitlab.com:axo/oranda.git", &ReleasesSource::Git).unwrap();
+    assert_eq!(
+        gitlab.compare("v0.1.0", "v0.2.0"),
+        "https://gitlab.com/axo/oranda/-/compare/v0.1.0...v0.2.0"
+    );
+    assert_eq!(
+        gitlab.issue("12"),
+        "https://gitlab.com/axo/oranda/-/issues/12"
+    );
+
+    // Code and existing links are left alone
+    let html = markdown::to_html_with_links(
+        "Fixes #12, not `#13` or [#14](https://example.com)",
+        &SyntaxTheme::AgilaCobalt,
+        |text| github.linkify(text),
+    )
+    .unwrap();
+    assert!(html.contains(
+        r#"href="https://github.com/axodotdev/oranda/issues/12" rel="noopener noreferrer">#12</a>"#
+    ));
+    assert!(html.contains("<code>#13</code>"));
+    assert!(html.contains(r#"href="https://example.com" rel="noopener noreferrer">#14</a>"#));
+}
+
+#[test]
+fn it_lists_contributors_between_tags() {
+    let dir = TempDir::new().unwrap();
+    let git = |author: &str, args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", &format!("user.name={author}")])
+            .args(["-c", "user.email=axo@example.com"])
+            .args(args)
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git("axo", &["init", "--quiet"]);
+    git(
+        "Misha",
+        &["commit", "--quiet", "--allow-empty", "-m", "Start"],
+    );
+    git("axo", &["tag", "v1.0.0"]);
+    git(
+        "ashley",
+        &["commit", "--quiet", "--allow-empty", "-m", "Fix"],
+    );
+    git(
+        "Misha",
+        &["commit", "--quiet", "--allow-empty", "-m", "Docs"],
+    );
+    git(
+        "ashley",
+        &["commit", "--quiet", "--allow-empty", "-m", "Fix again"],
+    );
+    git("axo", &["tag", "v1.1.0"]);
+
+    let path = camino::Utf8Path::from_path(dir.path()).unwrap();
+    assert_eq!(
+        git::contributors(path, Some("v1.0.0"), "v1.1.0"),
+        ["ashley", "Misha"]
+    );
+    assert_eq!(git::contributors(path, None, "v1.0.0"), ["Misha"]);
+    assert!(git::contributors(path, Some("v0.1.0"), "v1.1.0").is_empty());
+}
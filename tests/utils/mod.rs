@@ -1,2 +1,3 @@
 pub mod snapshots;
+pub mod stand_in;
 pub mod tokio_utils;